@@ -1,14 +1,43 @@
+use std::fmt;
 use std::mem::size_of;
 use duplicate::duplicate;
 
+/// Describes why a struct could not be read from a byte slice
+#[derive(Debug)]
+pub enum Error {
+    /// The slice ends before the struct does. `needed` is the packed size
+    /// of the struct which has been expected at `offset`.
+    Truncated { offset: usize, needed: usize },
+
+    /// The bytes at `offset` do not form a valid instance of the struct,
+    /// e.g. because a field contains an unknown enum value.
+    InvalidData { offset: usize, reason: String },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Truncated { offset, needed } => write!(
+                f,
+                "data is truncated: expected {} bytes at offset 0x{:08x}",
+                needed, offset
+            ),
+            Error::InvalidData { offset, reason } => {
+                write!(f, "invalid data at offset 0x{:08x}: {}", offset, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 pub trait StructFromBytes<T: PackedSize = Self>: PackedSize {
     /// Creates an instance of `T` by parsing a slice of bytes.
     /// 
     /// This method assumes that the byte slice contains unaligned data.
-    fn from_bytes(slice: &[u8], offset: usize) -> std::io::Result<Box<Self>>;
+    fn from_bytes(slice: &[u8], offset: usize) -> Result<Box<Self>, Error>;
 }
 
-
 pub trait PackedSize {
     /// Returns the packed size of the struct. 
     /// 
@@ -34,8 +63,8 @@ pub trait PackedSize {
     [i64];
     [i128];
 )]
-impl PackedSize for int_type { fn packed_size() -> usize { return size_of::<int_type>(); } }
+impl PackedSize for int_type { fn packed_size() -> usize { size_of::<int_type>() } }
 
-impl PackedSize for [u8;8] { fn packed_size() -> usize { return 8 * size_of::<u8>(); } }
-impl PackedSize for [u16;4] { fn packed_size() -> usize { return 4 * size_of::<u16>(); } }
-impl PackedSize for [u16;10] { fn packed_size() -> usize { return 10 * size_of::<u16>(); } }
\ No newline at end of file
+impl PackedSize for [u8;8] { fn packed_size() -> usize { size_of::<[u8;8]>() } }
+impl PackedSize for [u16;4] { fn packed_size() -> usize { size_of::<[u16;4]>() } }
+impl PackedSize for [u16;10] { fn packed_size() -> usize { size_of::<[u16;10]>() } }
\ No newline at end of file
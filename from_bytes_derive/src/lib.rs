@@ -5,7 +5,6 @@ use quote::{quote, quote_spanned};
 use syn::spanned::Spanned;
 use syn::{Data, DeriveInput, Fields, Index};
 use std::mem::size_of;
-use syn;
 
 
 #[proc_macro_derive(StructFromBytes)]
@@ -18,11 +17,15 @@ fn impl_from_bytes(ast: &syn::DeriveInput) -> TokenStream {
     let name = &ast.ident;
     let gen = quote! {
         impl StructFromBytes for #name {
-            fn from_bytes(slice: &[u8], offset: usize) -> std::io::Result<Box<Self>> {
+            fn from_bytes(slice: &[u8], offset: usize) -> Result<Box<Self>, ::from_bytes::Error> {
                 let size = Self::packed_size();
-                match Self::unpack_from_slice(&slice[offset..offset+size]) {
+                let bytes = match offset.checked_add(size).and_then(|end| slice.get(offset..end)) {
+                    Some(bytes) => bytes,
+                    None        => return Err(::from_bytes::Error::Truncated { offset, needed: size })
+                };
+                match Self::unpack_from_slice(bytes) {
                     Ok(v)    => Ok(Box::new(v)),
-                    Err(why) => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{:?}", why)))
+                    Err(why) => Err(::from_bytes::Error::InvalidData { offset, reason: format!("{:?}", why) })
                 }
             }
         }
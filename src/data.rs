@@ -0,0 +1,123 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// the backing store of a [PEFile](crate::PEFile)
+///
+/// A PE image can either be memory-mapped from a file or be held in memory.
+/// In every case, the parser only sees a contiguous byte slice.
+pub enum ImageData {
+    /// a memory-mapped file
+    Mapped(memmap::Mmap),
+
+    /// a buffer which is owned by the [PEFile](crate::PEFile)
+    Owned(Vec<u8>),
+
+    /// a buffer which is shared with other parts of the application
+    Shared(Arc<[u8]>),
+}
+
+impl Deref for ImageData {
+    type Target = [u8];
+    fn deref(&self) -> &Self::Target {
+        match self {
+            ImageData::Mapped(mmap) => &mmap[..],
+            ImageData::Owned(vec) => &vec[..],
+            ImageData::Shared(arc) => &arc[..],
+        }
+    }
+}
+
+impl AsRef<[u8]> for ImageData {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl From<memmap::Mmap> for ImageData {
+    fn from(mmap: memmap::Mmap) -> Self {
+        ImageData::Mapped(mmap)
+    }
+}
+
+impl From<Vec<u8>> for ImageData {
+    fn from(vec: Vec<u8>) -> Self {
+        ImageData::Owned(vec)
+    }
+}
+
+impl From<Arc<[u8]>> for ImageData {
+    fn from(arc: Arc<[u8]>) -> Self {
+        ImageData::Shared(arc)
+    }
+}
+
+/// copies the slice, because [PEFile](crate::PEFile) does not borrow its data
+impl From<&[u8]> for ImageData {
+    fn from(slice: &[u8]) -> Self {
+        ImageData::Owned(slice.to_vec())
+    }
+}
+
+/// describes how the sections of a PE image are arranged in its [ImageData]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImageLayout {
+    /// the image has been read from disk, so that every section is stored at its `PointerToRawData`
+    #[default]
+    File,
+
+    /// the image has been dumped from memory, so that every section is stored at its `VirtualAddress`
+    Memory,
+
+    /// the layout is unknown and will be guessed while parsing the image.
+    /// [PEFile::layout](crate::PEFile::layout) returns the result.
+    Detect,
+}
+
+const PAGE_SIZE: usize = 0x1000;
+
+/// returns `true` if the bytes at `offset` are not all zero.
+/// At most `length` bytes are checked, and the check stops at the end of the page.
+fn has_content(image: &[u8], offset: usize, length: usize) -> bool {
+    let length = length.min(PAGE_SIZE - offset % PAGE_SIZE);
+    let end = offset.saturating_add(length).min(image.len());
+    image.get(offset..end).map(|s| s.iter().any(|b| *b != 0)).unwrap_or(false)
+}
+
+/// guesses whether `image` has been read from disk or dumped from memory.
+///
+/// A memory dump contains at least `size_of_image` bytes, and its sections contain data
+/// at their RVAs. At the same time, the file offsets of the sections typically point
+/// into zeroed padding (e.g. the rest of the header page), or into other sections.
+/// Only the first page at both locations is checked, because sections often end with zeroes.
+/// If both layouts look equally plausible, the file layout is assumed.
+pub(crate) fn detect_layout(
+    image: &[u8],
+    size_of_image: usize,
+    sections: &[crate::winnt::IMAGE_SECTION_HEADER],
+) -> ImageLayout {
+    if image.len() < size_of_image {
+        return ImageLayout::File;
+    }
+
+    let mut votes: isize = 0;
+    for section in sections
+        .iter()
+        .filter(|s| s.SizeOfRawData != 0 && s.PointerToRawData != s.VirtualAddress)
+    {
+        let length = section.SizeOfRawData.min(section.Misc.max(1)) as usize;
+        let at_raw = has_content(image, section.PointerToRawData as usize, length);
+        let at_rva = has_content(image, section.VirtualAddress as usize, length);
+        match (at_raw, at_rva) {
+            (false, true) => votes += 1,
+            (true, false) => votes -= 1,
+            _ => (),
+        }
+    }
+
+    log::debug!("layout detection: {} votes for memory layout", votes);
+    if votes > 0 {
+        ImageLayout::Memory
+    } else {
+        ImageLayout::File
+    }
+}
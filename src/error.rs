@@ -0,0 +1,113 @@
+use std::fmt;
+use crate::winnt::IMAGE_DIRECTORY_ENTRY;
+
+/// result type which is used throughout this crate
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// describes why a PE file could not be parsed
+#[derive(Debug)]
+pub enum Error {
+    /// an I/O error occured while opening or reading the file
+    Io(std::io::Error),
+
+    /// the file does not start with `MZ`. Contains the value which was found instead.
+    BadDosMagic(u16),
+
+    /// the NT headers do not start with `PE\0\0`. Contains the value which was found instead.
+    BadNtSignature([u8; 4]),
+
+    /// the optional header has a magic value which is neither PE32 nor PE32+
+    UnknownOptionalHeaderMagic(u16),
+
+    /// the data ends before a structure does
+    Truncated { offset: usize, needed: usize },
+
+    /// the data at `offset` cannot be interpreted, e.g. because of an unknown enum value
+    InvalidData { offset: usize, reason: String },
+
+    /// the RVA is not contained in any section
+    RvaNotMapped(usize),
+
+    /// the resource directory at the given offset (relative to the resources section)
+    /// contains itself, directly or indirectly
+    ResourceLoop(usize),
+
+    /// the image has no such data directory
+    MissingDirectory(IMAGE_DIRECTORY_ENTRY),
+
+    /// the image has no optional header, so it cannot be loaded
+    MissingOptionalHeader,
+
+    /// the resource tree is not structured as expected
+    InvalidResourceLayout(String),
+
+    /// the image has not been loaded from a file, so that related files cannot be found
+    MissingFileName,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(why) => write!(f, "I/O error: {}", why),
+            Error::BadDosMagic(magic) => write!(f, "illegal DOS magic: 0x{:04x}", magic),
+            Error::BadNtSignature(signature) => write!(f, "illegal NT signature: {:?}", signature),
+            Error::UnknownOptionalHeaderMagic(magic) => {
+                write!(f, "illegal optional header magic: 0x{:04x}", magic)
+            }
+            Error::Truncated { offset, needed } => write!(
+                f,
+                "data is truncated: expected {} bytes at offset 0x{:08x}",
+                needed, offset
+            ),
+            Error::InvalidData { offset, reason } => {
+                write!(f, "invalid data at offset 0x{:08x}: {}", offset, reason)
+            }
+            Error::RvaNotMapped(rva) => write!(f, "rva 0x{:08x} is not mapped by any section", rva),
+            Error::ResourceLoop(offset) => {
+                write!(f, "resource directory at offset 0x{:08x} contains itself", offset)
+            }
+            Error::MissingDirectory(entry) => write!(f, "there is no {:?}", entry),
+            Error::MissingOptionalHeader => write!(f, "there is no optional header"),
+            Error::InvalidResourceLayout(reason) => {
+                write!(f, "unexpected resource directory layout: {}", reason)
+            }
+            Error::MissingFileName => write!(f, "the image has not been loaded from a file"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(why) => Some(why),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(why: std::io::Error) -> Self {
+        Error::Io(why)
+    }
+}
+
+impl From<from_bytes::Error> for Error {
+    fn from(why: from_bytes::Error) -> Self {
+        match why {
+            from_bytes::Error::Truncated { offset, needed } => Error::Truncated { offset, needed },
+            from_bytes::Error::InvalidData { offset, reason } => {
+                Error::InvalidData { offset, reason }
+            }
+        }
+    }
+}
+
+/// allows to use `?` on this crate's results in functions which return a [std::io::Result]
+impl From<Error> for std::io::Error {
+    fn from(why: Error) -> Self {
+        match why {
+            Error::Io(why) => why,
+            why => std::io::Error::new(std::io::ErrorKind::InvalidData, why),
+        }
+    }
+}
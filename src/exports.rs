@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+use byteorder::{ByteOrder, LittleEndian};
+use crate::error::{Error, Result};
+use crate::pefile::PEFile;
+use crate::winnt::*;
+
+/// the contents of the export directory of a PE image
+pub struct ExportDirectory {
+    /// the name of the DLL, as stored in the export directory
+    pub name: String,
+    pub directory: IMAGE_EXPORT_DIRECTORY,
+    pub functions: Vec<ExportedFunction>,
+}
+
+/// a single entry of the export address table
+pub struct ExportedFunction {
+    pub ordinal: u32,
+
+    /// the names of the function, in the order of the export name table. This is empty
+    /// if the function is exported by ordinal only, and contains several names if the
+    /// function is exported under aliases.
+    pub names: Vec<String>,
+
+    /// RVA of the exported function (or of the forwarder string)
+    pub rva: u32,
+
+    /// contains something like `NTDLL.RtlAllocateHeap` if the function is forwarded to another DLL
+    pub forwarder: Option<String>,
+}
+
+impl ExportedFunction {
+    /// returns the first name of the function, or `None` if it is exported by ordinal only
+    pub fn name(&self) -> Option<&str> {
+        self.names.first().map(String::as_str)
+    }
+}
+
+/// returns the size of a table with `count` entries of `width` bytes, which starts at `rva`
+fn table_size(rva: u32, count: u32, width: usize) -> Result<usize> {
+    (count as usize)
+        .checked_mul(width)
+        .ok_or(Error::Truncated { offset: rva as usize, needed: usize::MAX })
+}
+
+/// reads a table of `count` little endian `u32` values
+fn read_u32_table(pefile: &PEFile, rva: u32, count: u32) -> Result<Vec<u32>> {
+    let bytes = pefile.bytes_at_rva(rva as usize, table_size(rva, count, 4)?)?;
+    Ok(bytes.chunks_exact(4).map(LittleEndian::read_u32).collect())
+}
+
+pub(crate) fn read_exports(pefile: &PEFile, export_directory: &IMAGE_DATA_DIRECTORY) -> Result<ExportDirectory> {
+    let directory = pefile.struct_at_rva::<IMAGE_EXPORT_DIRECTORY>(export_directory.VirtualAddress as usize)?;
+    let name = pefile.cstring_at_rva(directory.Name as usize)?;
+    log::debug!("found export directory of {} with {} functions", name, directory.NumberOfFunctions);
+
+    // map indices of the address table to names
+    let mut names: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+    if directory.NumberOfNames > 0 {
+        let name_rvas = read_u32_table(pefile, directory.AddressOfNames, directory.NumberOfNames)?;
+        let ordinals = pefile.bytes_at_rva(
+            directory.AddressOfNameOrdinals as usize,
+            table_size(directory.AddressOfNameOrdinals, directory.NumberOfNames, 2)?,
+        )?;
+        for (name_rva, index) in name_rvas.iter().zip(ordinals.chunks_exact(2)) {
+            let index = LittleEndian::read_u16(index) as u32;
+            names.entry(index).or_default().push(pefile.cstring_at_rva(*name_rva as usize)?);
+        }
+    }
+
+    let forwarder_range = export_directory.VirtualAddress as usize
+        ..export_directory.VirtualAddress as usize + export_directory.Size as usize;
+
+    let mut functions = Vec::new();
+    let addresses = read_u32_table(pefile, directory.AddressOfFunctions, directory.NumberOfFunctions)?;
+    for (index, rva) in addresses.into_iter().enumerate() {
+        // unused entries of the address table
+        if rva == 0 {
+            continue;
+        }
+
+        let forwarder = if forwarder_range.contains(&(rva as usize)) {
+            Some(pefile.cstring_at_rva(rva as usize)?)
+        } else {
+            None
+        };
+
+        functions.push(ExportedFunction {
+            ordinal: directory.Base.wrapping_add(index as u32),
+            names: names.remove(&(index as u32)).unwrap_or_default(),
+            rva,
+            forwarder,
+        });
+    }
+
+    Ok(ExportDirectory {
+        name,
+        directory: *directory,
+        functions,
+    })
+}
@@ -0,0 +1,51 @@
+use from_bytes::*;
+use crate::error::Result;
+use crate::utils::cstring_from_slice;
+use crate::winnt::*;
+
+/// a DLL which the image has been bound against
+pub struct BoundImportedDll {
+    pub name: String,
+    pub descriptor: IMAGE_BOUND_IMPORT_DESCRIPTOR,
+
+    /// DLLs to which functions of this DLL are forwarded, and which have been bound as well
+    pub forwarders: Vec<BoundForwarder>,
+}
+
+pub struct BoundForwarder {
+    pub name: String,
+    pub forwarder_ref: IMAGE_BOUND_FORWARDER_REF,
+}
+
+/// parses the bound import directory, which is given as a byte slice,
+/// because all names are referenced by offsets relative to its start
+pub(crate) fn read_bound_imports(directory: &[u8]) -> Result<Vec<BoundImportedDll>> {
+    let mut dlls = Vec::new();
+    let mut offset = 0;
+    loop {
+        let descriptor = IMAGE_BOUND_IMPORT_DESCRIPTOR::from_bytes(directory, offset)?;
+        if descriptor.TimeDateStamp == 0 && descriptor.OffsetModuleName == 0 {
+            break;
+        }
+        offset += IMAGE_BOUND_IMPORT_DESCRIPTOR::packed_size();
+
+        let mut forwarders = Vec::new();
+        for _ in 0..descriptor.NumberOfModuleForwarderRefs {
+            let forwarder_ref = IMAGE_BOUND_FORWARDER_REF::from_bytes(directory, offset)?;
+            offset += IMAGE_BOUND_FORWARDER_REF::packed_size();
+            forwarders.push(BoundForwarder {
+                name: cstring_from_slice(directory, forwarder_ref.OffsetModuleName as usize)?,
+                forwarder_ref: *forwarder_ref,
+            });
+        }
+
+        let name = cstring_from_slice(directory, descriptor.OffsetModuleName as usize)?;
+        log::debug!("image is bound to {} (timestamp 0x{:08x})", name, descriptor.TimeDateStamp);
+        dlls.push(BoundImportedDll {
+            name,
+            descriptor: *descriptor,
+            forwarders,
+        });
+    }
+    Ok(dlls)
+}
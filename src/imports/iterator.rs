@@ -0,0 +1,71 @@
+use std::marker::PhantomData;
+use crate::error::Result;
+use crate::imports::*;
+use crate::pefile::PEFile;
+use crate::winnt::*;
+
+/// iterates over the descriptors of an import directory, which can either
+/// be [IMAGE_IMPORT_DESCRIPTOR]s or [IMAGE_DELAYLOAD_DESCRIPTOR]s
+pub struct ImportsIterator<'pefile, D: ImportDescriptor = IMAGE_IMPORT_DESCRIPTOR> {
+    pefile: &'pefile PEFile,
+
+    /// RVA of the next descriptor to be read, or `None` if we are done
+    next_descriptor: Option<usize>,
+
+    descriptor_type: PhantomData<D>,
+}
+
+impl<'pefile, D: ImportDescriptor> ImportsIterator<'pefile, D> {
+    pub fn new(pefile: &'pefile PEFile, import_directory: Option<usize>) -> Self {
+        Self {
+            pefile,
+            next_descriptor: import_directory,
+            descriptor_type: PhantomData,
+        }
+    }
+
+    pub fn do_next(&mut self) -> Result<Option<ImportedDll<D>>> {
+        let rva = match self.next_descriptor {
+            None => return Ok(None),
+            Some(rva) => rva,
+        };
+
+        let descriptor = self.pefile.struct_at_rva::<D>(rva)?;
+        if descriptor.is_null() {
+            self.next_descriptor = None;
+            return Ok(None);
+        }
+        self.next_descriptor = Some(rva + D::packed_size());
+
+        let address_base = descriptor.address_base(self.pefile.image_base());
+        let name_rva = to_rva(descriptor.name_address() as u64, address_base)?;
+        let name = self.pefile.cstring_at_rva(name_rva)?;
+        log::debug!("found import descriptor for {} at 0x{:08x}", name, rva);
+
+        let functions = read_thunks(
+            self.pefile,
+            to_rva(descriptor.lookup_table_address() as u64, address_base)?,
+            to_rva(descriptor.iat_address() as u64, address_base)?,
+            address_base,
+        )?;
+
+        Ok(Some(ImportedDll {
+            name,
+            descriptor: *descriptor,
+            functions,
+        }))
+    }
+}
+
+impl<'pefile, D: ImportDescriptor> Iterator for ImportsIterator<'pefile, D> {
+    type Item = Result<ImportedDll<D>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.do_next();
+
+        // we cannot know where the next descriptor is if this one is broken
+        if result.is_err() {
+            self.next_descriptor = None;
+        }
+        result.transpose()
+    }
+}
@@ -0,0 +1,167 @@
+mod iterator;
+mod bound;
+pub use iterator::*;
+pub use bound::*;
+
+use std::fmt;
+use from_bytes::StructFromBytes;
+use crate::error::{Error, Result};
+use crate::pefile::PEFile;
+use crate::winnt::*;
+
+/// a DLL which is imported by a PE image, together with all functions which are imported from it
+pub struct ImportedDll<D = IMAGE_IMPORT_DESCRIPTOR> {
+    pub name: String,
+    pub descriptor: D,
+    pub functions: Vec<ImportedFunction>,
+}
+
+/// a DLL which is loaded when one of its functions is called for the first time
+pub type DelayImportedDll = ImportedDll<IMAGE_DELAYLOAD_DESCRIPTOR>;
+
+/// a single slot of an import address table
+pub struct ImportedFunction {
+    /// RVA of the IAT slot, which is overwritten with the address of the function by the loader
+    pub iat_rva: u32,
+    pub symbol: ImportedSymbol,
+}
+
+/// describes how a function is imported
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportedSymbol {
+    ByName { hint: u16, name: String },
+    ByOrdinal(u16),
+}
+
+impl fmt::Display for ImportedSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportedSymbol::ByName { name, .. } => write!(f, "{}", name),
+            ImportedSymbol::ByOrdinal(ordinal) => write!(f, "#{}", ordinal),
+        }
+    }
+}
+
+/// common interface of [IMAGE_IMPORT_DESCRIPTOR] and [IMAGE_DELAYLOAD_DESCRIPTOR],
+/// which both describe an imported DLL
+pub trait ImportDescriptor: StructFromBytes + Copy {
+    /// returns `true` if this is the entry which terminates the directory
+    fn is_null(&self) -> bool;
+
+    /// address of the DLL name
+    fn name_address(&self) -> u32;
+
+    /// address of the table which contains names and ordinals of the imported functions
+    fn lookup_table_address(&self) -> u32;
+
+    /// address of the import address table
+    fn iat_address(&self) -> u32;
+
+    /// value which must be subtracted from all addresses to get RVAs
+    fn address_base(&self, image_base: u64) -> u64;
+}
+
+impl ImportDescriptor for IMAGE_IMPORT_DESCRIPTOR {
+    fn is_null(&self) -> bool {
+        IMAGE_IMPORT_DESCRIPTOR::is_null(self)
+    }
+
+    fn name_address(&self) -> u32 {
+        self.Name
+    }
+
+    fn lookup_table_address(&self) -> u32 {
+        // some linkers do not create an import lookup table, so we must use the IAT instead
+        if self.OriginalFirstThunk != 0 {
+            self.OriginalFirstThunk
+        } else {
+            self.FirstThunk
+        }
+    }
+
+    fn iat_address(&self) -> u32 {
+        self.FirstThunk
+    }
+
+    fn address_base(&self, _image_base: u64) -> u64 {
+        0
+    }
+}
+
+impl ImportDescriptor for IMAGE_DELAYLOAD_DESCRIPTOR {
+    fn is_null(&self) -> bool {
+        self.DllNameRVA == 0
+    }
+
+    fn name_address(&self) -> u32 {
+        self.DllNameRVA
+    }
+
+    fn lookup_table_address(&self) -> u32 {
+        self.ImportNameTableRVA
+    }
+
+    fn iat_address(&self) -> u32 {
+        self.ImportAddressTableRVA
+    }
+
+    fn address_base(&self, image_base: u64) -> u64 {
+        if self.is_rva_based() {
+            0
+        } else {
+            image_base
+        }
+    }
+}
+
+/// converts an address, which is either an RVA or a VA, into an RVA
+fn to_rva(address: u64, address_base: u64) -> Result<usize> {
+    address
+        .checked_sub(address_base)
+        .map(|rva| rva as usize)
+        .ok_or(Error::RvaNotMapped(address as usize))
+}
+
+/// reads a zero-terminated array of thunks (either of 32bit or of 64bit width,
+/// depending on the image type), starting at `lookup_rva`.
+///
+/// The slots of the import address table are expected to start at `iat_rva`.
+/// Names are referenced by addresses from which `address_base` must be subtracted.
+pub(crate) fn read_thunks(
+    pefile: &PEFile,
+    lookup_rva: usize,
+    iat_rva: usize,
+    address_base: u64,
+) -> Result<Vec<ImportedFunction>> {
+    let (width, ordinal_flag) = if pefile.is_64bit() {
+        (8, 1u64 << 63)
+    } else {
+        (4, 1u64 << 31)
+    };
+
+    let mut functions = Vec::new();
+    for idx in 0.. {
+        let bytes = pefile.bytes_at_rva(lookup_rva + idx * width, width)?;
+        let thunk = bytes.iter().rev().fold(0u64, |v, &b| (v << 8) | b as u64);
+        if thunk == 0 {
+            break;
+        }
+
+        let symbol = if thunk & ordinal_flag != 0 {
+            ImportedSymbol::ByOrdinal((thunk & 0xffff) as u16)
+        } else {
+            let hint_rva = to_rva(thunk, address_base)?;
+            let hint = pefile.bytes_at_rva(hint_rva, 2)?;
+            ImportedSymbol::ByName {
+                hint: hint[0] as u16 | ((hint[1] as u16) << 8),
+                name: pefile.cstring_at_rva(hint_rva + 2)?,
+            }
+        };
+
+        functions.push(ImportedFunction {
+            iat_rva: (iat_rva + idx * width) as u32,
+            symbol,
+        });
+    }
+    Ok(functions)
+}
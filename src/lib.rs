@@ -7,17 +7,102 @@ mod pefile;
 mod winnt;
 mod utils;
 mod msg;
+mod imports;
+mod exports;
+mod relocations;
+mod mapping;
+mod data;
+mod resources;
+mod error;
+mod wevt;
 
 pub use pefile::PEFile as PEFile;
+pub use error::{Error, Result};
+pub use data::{ImageData, ImageLayout};
+pub use imports::{
+    BoundForwarder,
+    BoundImportedDll,
+    DelayImportedDll,
+    ImportDescriptor,
+    ImportedDll,
+    ImportedFunction,
+    ImportedSymbol,
+    ImportsIterator};
+pub use exports::{ExportDirectory, ExportedFunction};
+pub use relocations::{Relocation, RelocationBlock, RelocationType, RelocationsIterator};
 pub use msg::Message as Message;
+pub use msg::{Insert, LangId, MessageResolver};
+pub use wevt::{
+    Channel,
+    Event,
+    Guid,
+    Keyword,
+    Level,
+    Manifest,
+    Opcode,
+    Provider,
+    Task,
+    Template,
+    TemplateItem,
+    ValueMap,
+    ValueMapEntry,
+    ValueMapKind,
+    XmlAttribute,
+    XmlElement,
+    XmlNode,
+    XmlValue};
+pub use resources::{
+    Accelerator,
+    AcceleratorTable,
+    AssemblyManifest,
+    dib_to_bmp,
+    Bitmap,
+    Dialog,
+    DialogControl,
+    DialogFont,
+    DependentAssembly,
+    Font,
+    FontDirEntry,
+    FontFormat,
+    IconGroup,
+    IconImage,
+    IconKind,
+    Menu,
+    MenuItem,
+    MuiConfig,
+    MuiResolver,
+    NameOrOrdinal,
+    ResourceData,
+    ResourceDirectoryVisitor,
+    ResourceEntry,
+    ResourceLanguageNode,
+    ResourceNameNode,
+    ResourceString,
+    ResourceTree,
+    ResourceType,
+    ResourceTypeNode,
+    StringTableIterator,
+    VersionInfo,
+    VersionString,
+    VersionStringTable};
 pub use winnt::{
+    IMAGE_BASE_RELOCATION,
+    IMAGE_BOUND_FORWARDER_REF,
+    IMAGE_BOUND_IMPORT_DESCRIPTOR,
     IMAGE_DATA_DIRECTORY,
+    IMAGE_DELAYLOAD_DESCRIPTOR,
     IMAGE_DIRECTORY_ENTRY,
     IMAGE_DOS_HEADER,
+    IMAGE_EXPORT_DIRECTORY,
     IMAGE_FILE_HEADER,
     IMAGE_FILE_HEADER_Machine,
+    IMAGE_IMPORT_DESCRIPTOR,
     IMAGE_NT_OPTIONAL_HEADER,
     IMAGE_OPTIONAL_HEADER,
     IMAGE_OPTIONAL_HEADER32,
     IMAGE_OPTIONAL_HEADER64,
-    IMAGE_SECTION_HEADER};
+    IMAGE_RESOURCE_DATA_ENTRY,
+    IMAGE_RESOURCE_DIRECTORY,
+    IMAGE_SECTION_HEADER,
+    VS_FIXEDFILEINFO,
+    EntryIdentifier};
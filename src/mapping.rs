@@ -0,0 +1,105 @@
+use crate::data::ImageLayout;
+use crate::error::{Error, Result};
+use crate::pefile::PEFile;
+use crate::winnt::IMAGE_FILE_HEADER;
+use from_bytes::PackedSize;
+
+/// offset of `SizeOfImage` in both `IMAGE_OPTIONAL_HEADER32` and `IMAGE_OPTIONAL_HEADER64`
+const SIZE_OF_IMAGE_OFFSET: usize = 56;
+
+fn align_up(value: usize, alignment: usize) -> usize {
+    if alignment == 0 {
+        value
+    } else {
+        value.div_ceil(alignment) * alignment
+    }
+}
+
+/// copies as much of `source` as fits into `image` at `offset`
+fn copy_clipped(image: &mut [u8], offset: usize, source: &[u8]) {
+    if offset < image.len() {
+        let length = source.len().min(image.len() - offset);
+        image[offset..offset + length].copy_from_slice(&source[..length]);
+        if length < source.len() {
+            log::warn!("data at rva 0x{:08x} exceeds SizeOfImage", offset);
+        }
+    }
+}
+
+/// creates a copy of the image, which is laid out like the Windows loader would do
+pub(crate) fn map_image(pefile: &PEFile) -> Result<Vec<u8>> {
+    let optional_header = pefile
+        .image_optional_header()
+        .as_ref()
+        .ok_or(Error::MissingOptionalHeader)?;
+    let section_alignment = optional_header.SectionAlignment() as usize;
+    let file = pefile.full_image();
+
+    let size_of_image = optional_header.SizeOfImage() as usize;
+
+    // the image cannot be larger than its headers and sections. Without this check,
+    // a tiny file could request gigabytes of memory
+    let end_of_sections = pefile
+        .sections()
+        .iter()
+        .map(|section| section.VirtualAddress as usize + section.Misc.max(section.SizeOfRawData) as usize)
+        .fold(optional_header.SizeOfHeaders() as usize, usize::max);
+    let size_of_image_offset = pefile.image_dos_header().e_lfanew as usize
+        + 4
+        + IMAGE_FILE_HEADER::packed_size()
+        + SIZE_OF_IMAGE_OFFSET;
+    if size_of_image > align_up(end_of_sections, section_alignment) {
+        return Err(Error::InvalidData {
+            offset: size_of_image_offset,
+            reason: format!(
+                "SizeOfImage 0x{:08x} exceeds the end of the last section 0x{:08x}",
+                size_of_image, end_of_sections
+            ),
+        });
+    }
+
+    let mut image = Vec::new();
+    image.try_reserve_exact(size_of_image).map_err(|_| Error::InvalidData {
+        offset: size_of_image_offset,
+        reason: format!("cannot allocate 0x{:08x} bytes for the image", size_of_image),
+    })?;
+    image.resize(size_of_image, 0);
+
+    // the image is already laid out as needed
+    if pefile.layout() == ImageLayout::Memory {
+        let length = file.len().min(image.len());
+        image[..length].copy_from_slice(&file[..length]);
+        return Ok(image);
+    }
+
+    let size_of_headers = (optional_header.SizeOfHeaders() as usize).min(file.len());
+    copy_clipped(&mut image, 0, &file[..size_of_headers]);
+
+    for section in pefile.sections() {
+        // the virtual size may be 0, in which case the loader uses the raw size
+        let virtual_size = match section.Misc {
+            0 => section.SizeOfRawData,
+            size => size,
+        } as usize;
+
+        // the loader maps the raw data up to the end of the last page of the section;
+        // everything after the raw data is filled with zeroes
+        let raw_offset = (section.PointerToRawData as usize).min(file.len());
+        let raw_size = (section.SizeOfRawData as usize)
+            .min(align_up(virtual_size, section_alignment))
+            .min(file.len() - raw_offset);
+
+        log::debug!(
+            "mapping section {} ({} bytes of raw data) to 0x{:08x}",
+            section.name(),
+            raw_size,
+            section.VirtualAddress
+        );
+        copy_clipped(
+            &mut image,
+            section.VirtualAddress as usize,
+            &file[raw_offset..raw_offset + raw_size],
+        );
+    }
+    Ok(image)
+}
@@ -0,0 +1,324 @@
+use std::fmt;
+use crate::msg::Message;
+
+/// a value which is inserted into a message by [Message::format]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Insert {
+    String(String),
+    Signed(i64),
+    Unsigned(u64),
+}
+
+impl Insert {
+    fn as_i64(&self) -> Option<i64> {
+        match self {
+            Insert::String(s) => s.trim().parse().ok(),
+            Insert::Signed(v) => Some(*v),
+            Insert::Unsigned(v) => Some(*v as i64),
+        }
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        match self {
+            Insert::String(s) => s.trim().parse().ok().or_else(|| self.as_i64().map(|v| v as u64)),
+            Insert::Signed(v) => Some(*v as u64),
+            Insert::Unsigned(v) => Some(*v),
+        }
+    }
+}
+
+impl fmt::Display for Insert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Insert::String(s) => write!(f, "{}", s),
+            Insert::Signed(v) => write!(f, "{}", v),
+            Insert::Unsigned(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+impl From<&str> for Insert {
+    fn from(s: &str) -> Self {
+        Insert::String(s.to_string())
+    }
+}
+
+impl From<String> for Insert {
+    fn from(s: String) -> Self {
+        Insert::String(s)
+    }
+}
+
+impl From<i32> for Insert {
+    fn from(v: i32) -> Self {
+        Insert::Signed(v.into())
+    }
+}
+
+impl From<i64> for Insert {
+    fn from(v: i64) -> Self {
+        Insert::Signed(v)
+    }
+}
+
+impl From<u32> for Insert {
+    fn from(v: u32) -> Self {
+        Insert::Unsigned(v.into())
+    }
+}
+
+impl From<u64> for Insert {
+    fn from(v: u64) -> Self {
+        Insert::Unsigned(v)
+    }
+}
+
+/// a printf-style format specification, as used in `%1!...!`
+#[derive(Default)]
+struct FormatSpec {
+    left_align: bool,
+    plus_sign: bool,
+    space_sign: bool,
+    zero_pad: bool,
+    alternate: bool,
+    width: Option<usize>,
+    precision: Option<usize>,
+
+    /// set by the `I64`, `ll` and `I` prefixes. Without them, integers are 32 bit wide
+    is_64bit: bool,
+    conversion: char,
+}
+
+impl FormatSpec {
+    /// parses the text between the exclamation marks.
+    ///
+    /// `star` is called for every `*`, and must return the next insert as number
+    fn parse<F: FnMut() -> Option<usize>>(spec: &str, mut star: F) -> Option<Self> {
+        let mut result = FormatSpec::default();
+        let mut chars = spec.chars().peekable();
+
+        while let Some(c) = chars.peek() {
+            match c {
+                '-' => result.left_align = true,
+                '+' => result.plus_sign = true,
+                ' ' => result.space_sign = true,
+                '0' => result.zero_pad = true,
+                '#' => result.alternate = true,
+                _ => break,
+            }
+            chars.next();
+        }
+
+        let mut number = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+            if chars.peek() == Some(&'*') {
+                chars.next();
+                return star();
+            }
+            let mut value = None;
+            while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                value = Some(value.unwrap_or(0usize).saturating_mul(10).saturating_add(digit as usize));
+                chars.next();
+            }
+            value
+        };
+
+        result.width = number(&mut chars);
+        if chars.peek() == Some(&'.') {
+            chars.next();
+            result.precision = Some(number(&mut chars).unwrap_or(0));
+        }
+
+        // `I` is the size of a pointer, which is 64 bit on current systems
+        let rest: String = chars.collect();
+        let (rest, prefix) = ["I64", "I32", "ll", "h", "l", "w", "I"]
+            .iter()
+            .find_map(|prefix| rest.strip_prefix(prefix).map(|rest| (rest, *prefix)))
+            .unwrap_or((&rest, ""));
+        result.is_64bit = matches!(prefix, "I64" | "ll" | "I");
+        let mut rest = rest.chars();
+        result.conversion = rest.next()?;
+        match rest.next() {
+            None => Some(result),
+            Some(_) => None,
+        }
+    }
+
+    fn pad(&self, sign: &str, body: String, allow_zero_pad: bool) -> String {
+        let length = sign.chars().count() + body.chars().count();
+        let width = self.width.unwrap_or(0);
+        if length >= width {
+            format!("{}{}", sign, body)
+        } else if self.left_align {
+            format!("{}{}{}", sign, body, " ".repeat(width - length))
+        } else if self.zero_pad && allow_zero_pad {
+            format!("{}{}{}", sign, "0".repeat(width - length), body)
+        } else {
+            format!("{}{}{}", " ".repeat(width - length), sign, body)
+        }
+    }
+
+    /// formats an integer, whose digits have already been converted
+    fn format_integer(&self, sign: &str, digits: String, prefix: &str) -> String {
+        let digits = match self.precision {
+            Some(0) if digits == "0" => String::new(),
+            Some(precision) if digits.len() < precision => {
+                format!("{}{}", "0".repeat(precision - digits.len()), digits)
+            }
+            _ => digits,
+        };
+        let sign = format!("{}{}", sign, prefix);
+        self.pad(&sign, digits, self.precision.is_none())
+    }
+
+    /// returns the insert as signed integer, truncated to the size given by the prefix
+    fn signed(&self, insert: &Insert) -> Option<i64> {
+        let value = insert.as_i64()?;
+        Some(if self.is_64bit { value } else { value as i32 as i64 })
+    }
+
+    /// returns the insert as unsigned integer, truncated to the size given by the prefix
+    fn unsigned(&self, insert: &Insert) -> Option<u64> {
+        let value = insert.as_u64()?;
+        Some(if self.is_64bit { value } else { value as u32 as u64 })
+    }
+
+    fn format(&self, insert: &Insert) -> Option<String> {
+        match self.conversion {
+            's' | 'S' => {
+                let text = insert.to_string();
+                let text = match self.precision {
+                    Some(precision) => text.chars().take(precision).collect(),
+                    None => text,
+                };
+                Some(self.pad("", text, false))
+            }
+            'c' | 'C' => {
+                let c = match insert {
+                    Insert::String(s) => s.chars().next()?,
+                    _ => std::char::from_u32(insert.as_u64()? as u32)?,
+                };
+                Some(self.pad("", c.to_string(), false))
+            }
+            'd' | 'i' => {
+                let value = self.signed(insert)?;
+                let sign = if value < 0 {
+                    "-"
+                } else if self.plus_sign {
+                    "+"
+                } else if self.space_sign {
+                    " "
+                } else {
+                    ""
+                };
+                Some(self.format_integer(sign, value.unsigned_abs().to_string(), ""))
+            }
+            'u' => Some(self.format_integer("", self.unsigned(insert)?.to_string(), "")),
+            'x' | 'X' | 'p' => {
+                let value = match self.conversion {
+                    'p' => insert.as_u64()?,
+                    _ => self.unsigned(insert)?,
+                };
+                let digits = match self.conversion {
+                    'x' => format!("{:x}", value),
+                    _ => format!("{:X}", value),
+                };
+                let prefix = match (self.alternate && value != 0, self.conversion) {
+                    (true, 'x') => "0x",
+                    (true, _) => "0X",
+                    _ => "",
+                };
+                Some(self.format_integer("", digits, prefix))
+            }
+            'o' => {
+                let value = self.unsigned(insert)?;
+                let prefix = if self.alternate && value != 0 { "0" } else { "" };
+                Some(self.format_integer("", format!("{:o}", value), prefix))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// replaces the FormatMessage directives in `text` by the `inserts`.
+///
+/// Inserts which do not exist (or which cannot be formatted as requested)
+/// are kept in the output, like `FormatMessage` does with `FORMAT_MESSAGE_IGNORE_INSERTS`.
+pub fn format_message(text: &str, inserts: &[Insert]) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        match c {
+            // messages are NUL-terminated
+            '\0' => break,
+            '%' => (),
+            c => {
+                result.push(c);
+                continue;
+            }
+        }
+
+        match chars.next() {
+            None => result.push('%'),
+            Some((_, '0')) => return result,
+            Some((_, 'n')) => result.push_str("\r\n"),
+            Some((_, 'r')) => result.push('\r'),
+            Some((_, 't')) => result.push('\t'),
+            Some((_, 'b')) => result.push(' '),
+            Some((_, first)) if first.is_ascii_digit() => {
+                let mut number = first.to_digit(10).unwrap() as usize;
+                if let Some((_, second)) = chars.peek().filter(|(_, c)| c.is_ascii_digit()) {
+                    number = number * 10 + second.to_digit(10).unwrap() as usize;
+                    chars.next();
+                }
+
+                // an optional format specification, which is enclosed in exclamation marks
+                let mut spec = "s";
+                if let Some((spec_start, '!')) = chars.peek().copied() {
+                    if let Some(length) = text[spec_start + 1..].find('!') {
+                        spec = &text[spec_start + 1..spec_start + 1 + length];
+                        while chars.peek().is_some_and(|(idx, _)| *idx <= spec_start + 1 + length) {
+                            chars.next();
+                        }
+                    }
+                }
+                let end = chars.peek().map(|(idx, _)| *idx).unwrap_or(text.len());
+
+                // `*` uses the inserts following the current one
+                let mut next_insert = number;
+                let formatted = FormatSpec::parse(spec, || {
+                    next_insert += 1;
+                    inserts.get(next_insert - 1)?.as_u64().map(|v| v as usize)
+                })
+                .and_then(|spec| spec.format(inserts.get(number.checked_sub(1)?)?));
+
+                match formatted {
+                    Some(formatted) => result.push_str(&formatted),
+                    None => result.push_str(&text[start..end]),
+                }
+            }
+
+            // all other characters are escaped, e.g. `%%`, `%.` and `%!`
+            Some((_, c)) => result.push(c),
+        }
+    }
+    result
+}
+
+impl Message {
+    /// renders the message text like `FormatMessage` does, replacing `%1` to `%99`
+    /// by the corresponding `inserts`.
+    ///
+    /// Inserts may have a printf-like format specification, such as `%1!08x!`.
+    /// The escape sequences `%n`, `%r`, `%t`, `%b`, `%%`, `%.`, `%!` and `%0` are supported.
+    ///
+    /// # Example
+    /// ```
+    /// use libpefile::*;
+    /// let msg = Message::new(1, 0x0409, "%1 logged on (%2!04x!)%n%0".to_string());
+    /// assert_eq!(msg.format(&["alice".into(), 42u32.into()]), "alice logged on (002a)\r\n");
+    /// ```
+    pub fn format(&self, inserts: &[Insert]) -> String {
+        format_message(&self.text, inserts)
+    }
+}
@@ -1,6 +1,14 @@
+mod format;
 mod iterator;
+mod langid;
+mod resolver;
+mod table;
 mod visitor;
+pub use format::*;
 pub use iterator::*;
+pub use langid::*;
+pub use resolver::*;
+pub use table::*;
 pub use visitor::*;
 
 pub struct Message {
@@ -17,4 +25,9 @@ impl Message {
             text,
         }
     }
+
+    /// returns the language of this message
+    pub fn language(&self) -> LangId {
+        LangId::from(self.lang_id)
+    }
 }
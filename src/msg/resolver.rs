@@ -0,0 +1,126 @@
+use std::cell::OnceCell;
+use crate::msg::{find_message_with_fallback, format_message, Insert, MessageTable};
+use crate::pefile::PEFile;
+use crate::error::Result;
+
+/// renders event messages and expands the `%%NNNN` references to parameter messages
+/// which they contain.
+///
+/// Event log providers may specify a `ParameterMessageFile` (e.g. `msobjs.dll` for the
+/// security auditing messages in `msaudite.dll`). Insertion strings of the form `%%NNNN`
+/// refer to message `NNNN` in one of these files. If no parameter file contains the message,
+/// it is searched in the message file itself.
+///
+/// # Example
+/// ```
+/// use libpefile::*;
+/// # use std::path::PathBuf;
+/// # fn main() -> Result<()> {
+/// # let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+/// # let dll_file = PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir));
+/// let msaudite = PEFile::new(dll_file)?;
+/// let resolver = MessageResolver::new(&msaudite).with_language(0x0409);
+///
+/// // %%2 refers to message 2 of msaudite.dll, because there are no parameter files
+/// let text = resolver.format(516, &["%%2".into()])?.unwrap();
+/// assert!(text.ends_with("Number of audit messages discarded:\tLogon/Logoff\r\n"));
+/// # Ok(())
+/// # }
+/// ```
+pub struct MessageResolver<'pefile> {
+    message_file: &'pefile PEFile,
+    parameter_files: Vec<&'pefile PEFile>,
+    lang_id: u32,
+
+    /// the message tables of the parameter files, followed by those of the message file.
+    /// They are read on the first lookup, so that the resource tree is not walked for every message.
+    tables: OnceCell<Vec<Vec<MessageTable<'pefile>>>>,
+}
+
+impl<'pefile> MessageResolver<'pefile> {
+    /// creates a resolver for the messages in `message_file`, which uses English (United States)
+    pub fn new(message_file: &'pefile PEFile) -> Self {
+        Self {
+            message_file,
+            parameter_files: Vec::new(),
+            lang_id: 0x0409,
+            tables: OnceCell::new(),
+        }
+    }
+
+    /// adds a file which is searched for parameter messages. Files are searched in the order
+    /// in which they have been added.
+    pub fn with_parameter_file(mut self, parameter_file: &'pefile PEFile) -> Self {
+        self.parameter_files.push(parameter_file);
+        self.tables = OnceCell::new();
+        self
+    }
+
+    /// sets the preferred language. Other languages are used as described in
+    /// [PEFile::message_with_fallback]
+    pub fn with_language(mut self, lang_id: u32) -> Self {
+        self.lang_id = lang_id;
+        self
+    }
+
+    /// returns the message tables of all parameter files, followed by those of the message file
+    fn tables(&self) -> Result<&[Vec<MessageTable<'pefile>>]> {
+        if let Some(tables) = self.tables.get() {
+            return Ok(tables);
+        }
+        let tables = self
+            .parameter_files
+            .iter()
+            .chain(std::iter::once(&self.message_file))
+            .map(|pefile| pefile.message_tables())
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self.tables.get_or_init(|| tables))
+    }
+
+    /// returns the text of the parameter message `param_id`, without trailing line breaks
+    pub fn parameter(&self, param_id: u32) -> Result<Option<String>> {
+        for tables in self.tables()? {
+            if let Some(msg) = find_message_with_fallback(tables, param_id, self.lang_id)? {
+                let text = format_message(&msg.text, &[]);
+                return Ok(Some(text.trim_end_matches(['\r', '\n']).to_string()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// replaces all `%%NNNN` references in `text` by the corresponding parameter messages.
+    ///
+    /// References which cannot be resolved are kept. Parameter messages are not expanded
+    /// recursively.
+    pub fn expand_parameters(&self, text: &str) -> Result<String> {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(position) = rest.find("%%") {
+            result.push_str(&rest[..position]);
+            let reference = &rest[position..];
+            let digits = reference[2..].bytes().take_while(u8::is_ascii_digit).count();
+            let param_id = reference[2..2 + digits].parse::<u32>().ok();
+
+            match param_id.map(|id| self.parameter(id)).transpose()?.flatten() {
+                Some(parameter) => result.push_str(&parameter),
+                None => result.push_str(&reference[..2 + digits]),
+            }
+            rest = &reference[2 + digits..];
+        }
+        result.push_str(rest);
+        Ok(result)
+    }
+
+    /// renders the message `msg_id` with the given `inserts`, and expands all
+    /// `%%NNNN` references in the result.
+    ///
+    /// Returns `None` if the message file does not contain the message.
+    pub fn format(&self, msg_id: u32, inserts: &[Insert]) -> Result<Option<String>> {
+        // the tables of the message file are always the last entry
+        let tables = self.tables()?;
+        match find_message_with_fallback(&tables[tables.len() - 1], msg_id, self.lang_id)? {
+            None => Ok(None),
+            Some(msg) => Ok(Some(self.expand_parameters(&msg.format(inserts))?)),
+        }
+    }
+}
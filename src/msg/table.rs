@@ -0,0 +1,178 @@
+use crate::msg::{LangId, Message, MessagesIterator};
+use crate::pefile::*;
+use crate::winnt::*;
+use crate::error::{Error, Result};
+use crate::utils::bytes_at;
+use encoding_rs::*;
+use from_bytes::*;
+
+/// encodings of message texts, indexed by `MESSAGE_RESOURCE_ENTRY::Flags`
+const ENCODINGS: [&Encoding; 2] = [WINDOWS_1252, UTF_16LE];
+
+/// a single MESSAGE_TABLE resource, which contains the messages of one language
+#[derive(Clone)]
+pub struct MessageTable<'pefile> {
+    pefile: &'pefile PEFile,
+    lang_id: u32,
+
+    /// offset of the `MESSAGE_RESOURCE_DATA` in the image
+    rde_offset: usize,
+    number_of_blocks: u32,
+}
+
+impl<'pefile> MessageTable<'pefile> {
+    pub fn new(
+        pefile: &'pefile PEFile,
+        lang_id: u32,
+        resource_entry: &IMAGE_RESOURCE_DATA_ENTRY,
+    ) -> Result<Self> {
+        let rva = resource_entry.OffsetToData as usize;
+        let rde_offset = pefile
+            .get_raw_address(rva)
+            .ok_or(Error::RvaNotMapped(rva))?;
+        let mrd = MESSAGE_RESOURCE_DATA::from_bytes(pefile.full_image(), rde_offset)?;
+
+        Ok(Self {
+            pefile,
+            lang_id,
+            rde_offset,
+            number_of_blocks: mrd.NumberOfBlocks,
+        })
+    }
+
+    /// recreates a message table which has already been read by [MessageTable::new]
+    pub(crate) fn from_parts(
+        pefile: &'pefile PEFile,
+        lang_id: u32,
+        rde_offset: usize,
+        number_of_blocks: u32,
+    ) -> Self {
+        Self {
+            pefile,
+            lang_id,
+            rde_offset,
+            number_of_blocks,
+        }
+    }
+
+    pub fn lang_id(&self) -> u32 {
+        self.lang_id
+    }
+
+    pub fn number_of_blocks(&self) -> u32 {
+        self.number_of_blocks
+    }
+
+    /// offset of the `MESSAGE_RESOURCE_DATA` in the image
+    pub fn rde_offset(&self) -> usize {
+        self.rde_offset
+    }
+
+    /// reads the block with the given index
+    pub fn block(&self, index: u32) -> Result<Box<MESSAGE_RESOURCE_BLOCK>> {
+        let offset = (index as usize)
+            .checked_mul(MESSAGE_RESOURCE_BLOCK::packed_size())
+            .and_then(|o| o.checked_add(self.rde_offset + MESSAGE_RESOURCE_DATA::packed_size()))
+            .ok_or(Error::Truncated {
+                offset: self.rde_offset,
+                needed: usize::MAX,
+            })?;
+        Ok(MESSAGE_RESOURCE_BLOCK::from_bytes(self.pefile.full_image(), offset)?)
+    }
+
+    /// decodes the entry at `entry_offset`, and returns the message
+    /// together with the size of the entry
+    pub fn read_entry(&self, entry_offset: usize, msg_id: u32) -> Result<(Message, usize)> {
+        let image = self.pefile.full_image();
+        let entry = MESSAGE_RESOURCE_ENTRY::from_bytes(image, entry_offset)?;
+        let text_offset = entry_offset + MESSAGE_RESOURCE_ENTRY::packed_size();
+        let message_length = (entry.Length as usize)
+            .checked_sub(MESSAGE_RESOURCE_ENTRY::packed_size())
+            .ok_or_else(|| Error::InvalidData {
+                offset: entry_offset,
+                reason: format!("message entry is too short: {} bytes", entry.Length),
+            })?;
+        let encoding = ENCODINGS.get(entry.Flags as usize).ok_or_else(|| Error::InvalidData {
+            offset: entry_offset,
+            reason: format!("unknown message encoding: 0x{:04x}", entry.Flags),
+        })?;
+
+        let text = encoding
+            .decode(bytes_at(image, text_offset, message_length)?)
+            .0
+            .to_string();
+        Ok((Message::new(msg_id, self.lang_id, text), entry.Length as usize))
+    }
+
+    /// searches the message with the given id.
+    ///
+    /// The blocks are sorted by their ids, so a binary search is used to find the block.
+    /// Inside the block, only the lengths of the preceding entries are read.
+    pub fn message(&self, msg_id: u32) -> Result<Option<Message>> {
+        let mut low = 0;
+        let mut high = self.number_of_blocks;
+        while low < high {
+            let index = low + (high - low) / 2;
+            let block = self.block(index)?;
+            if msg_id < block.LowId {
+                high = index;
+            } else if msg_id > block.HighId {
+                low = index + 1;
+            } else {
+                let mut entry_offset = self.rde_offset + block.OffsetToEntries as usize;
+                for _ in block.LowId..msg_id {
+                    let entry =
+                        MESSAGE_RESOURCE_ENTRY::from_bytes(self.pefile.full_image(), entry_offset)?;
+                    if (entry.Length as usize) < MESSAGE_RESOURCE_ENTRY::packed_size() {
+                        return Err(Error::InvalidData {
+                            offset: entry_offset,
+                            reason: format!("message entry is too short: {} bytes", entry.Length),
+                        });
+                    }
+                    entry_offset += entry.Length as usize;
+                }
+                return Ok(Some(self.read_entry(entry_offset, msg_id)?.0));
+            }
+        }
+        Ok(None)
+    }
+
+    /// returns an iterator over all messages of this table
+    pub fn iter(&self) -> MessagesIterator<'pefile> {
+        MessagesIterator::new(self.clone())
+    }
+}
+
+/// searches a single message in `tables`, in the order described in [PEFile::message_with_fallback]
+pub(crate) fn find_message_with_fallback(
+    tables: &[MessageTable<'_>],
+    msg_id: u32,
+    lang_id: u32,
+) -> Result<Option<Message>> {
+    let requested = LangId::from(lang_id);
+    let rank = |table: &&MessageTable| {
+        let lang = LangId::from(table.lang_id());
+        if table.lang_id() == lang_id {
+            0
+        } else if lang == LangId::new(requested.primary_language(), 0) {
+            1
+        } else if lang.primary_language() == requested.primary_language() {
+            2
+        } else if lang == LangId::NEUTRAL {
+            3
+        } else if lang == LangId::EN_US {
+            4
+        } else {
+            5
+        }
+    };
+    let mut tables: Vec<&MessageTable> = tables.iter().collect();
+    tables.sort_by_key(rank);
+
+    for table in tables {
+        if let Some(message) = table.message(msg_id)? {
+            return Ok(Some(message));
+        }
+    }
+    Ok(None)
+}
@@ -1,71 +1,35 @@
 use crate::winnt::*;
 use crate::pefile::*;
 use crate::msg::*;
-
-#[allow(dead_code,non_camel_case_types)]
-enum ResourceType {
-    RT_CURSOR = 1,
-    RT_BITMAP = 2,
-    RT_ICON = 3,
-    RT_MENU = 4,
-    RT_DIALOG = 5,
-    RT_STRING = 6,
-    RT_FONTDIR = 7,
-    RT_FONT = 8,
-    RT_ACCELERATOR = 9,
-    RT_RCDATA = 10,
-    RT_MESSAGETABLE = 11,
-}
-
-
-pub trait ResourceDirectoryVisitor {
-    fn init(&mut self) {}
-    fn finalize(&mut self) {}
-
-    fn enter_resource_directory(
-        &mut self,
-        dir: &IMAGE_RESOURCE_DIRECTORY,
-        identifier: &EntryIdentifier,
-    ) -> std::io::Result<()>;
-    fn leave_resource_directory(
-        &mut self,
-        dir: &IMAGE_RESOURCE_DIRECTORY,
-        identifier: &EntryIdentifier,
-    ) -> std::io::Result<()>;
-
-    fn visit_resource_data_entry(
-        &mut self,
-        entry: &IMAGE_RESOURCE_DATA_ENTRY,
-        identifier: &EntryIdentifier,
-    ) -> std::io::Result<()>;
-}
+use crate::error::{Error, Result};
+use crate::resources::{ResourceDirectoryVisitor, ResourceType};
 
 pub struct MessageTableVisitor<'pefile> {
     id_stack: Vec<EntryIdentifier>,
-    iterators: Vec<MessagesIterator<'pefile>>,
+    tables: Vec<MessageTable<'pefile>>,
     pefile: &'pefile PEFile
 }
 impl<'pefile> MessageTableVisitor<'pefile> {
     pub fn new(pefile: &'pefile PEFile) -> Self {
         MessageTableVisitor {
             id_stack: Vec::new(),
-            iterators: Vec::new(),
+            tables: Vec::new(),
             pefile
         }
     }
 
-    pub fn into_iter(self) -> impl Iterator<Item=std::io::Result<Message>> + 'pefile {
-        self.iterators.into_iter().flat_map(|i| i)
+    pub fn into_iter(self) -> impl Iterator<Item=Result<Message>> + 'pefile {
+        self.tables.into_iter().flat_map(|table| table.iter())
+    }
+
+    /// returns the message tables of all languages, in the order in which they were found
+    pub fn into_tables(self) -> Vec<MessageTable<'pefile>> {
+        self.tables
     }
 
     fn is_in_messagetable(&self) -> bool {
         match self.id_stack.first() {
-            Some(f) => match f {
-                EntryIdentifier::Id(id) => {
-                    *id == (ResourceType::RT_MESSAGETABLE as u16)
-                }
-                _ => false
-            }
+            Some(EntryIdentifier::Id(id)) => *id == (ResourceType::RT_MESSAGETABLE as u16),
             _ => false
         }
     }
@@ -75,7 +39,7 @@ impl<'pefile> ResourceDirectoryVisitor for MessageTableVisitor<'pefile> {
         &mut self,
         _dir: &IMAGE_RESOURCE_DIRECTORY,
         identifier: &EntryIdentifier,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         match identifier {
             EntryIdentifier::NoIdentifier => (),
             _ => self.id_stack.push(identifier.clone()),
@@ -86,7 +50,7 @@ impl<'pefile> ResourceDirectoryVisitor for MessageTableVisitor<'pefile> {
         &mut self,
         _dir: &IMAGE_RESOURCE_DIRECTORY,
         identifier: &EntryIdentifier,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         match identifier {
             EntryIdentifier::NoIdentifier => (),
             _ => { let _ = self.id_stack.pop(); }
@@ -97,18 +61,22 @@ impl<'pefile> ResourceDirectoryVisitor for MessageTableVisitor<'pefile> {
     fn visit_resource_data_entry(
         &mut self,
         entry: &IMAGE_RESOURCE_DATA_ENTRY,
-        _identifier: &EntryIdentifier,
-    ) -> std::io::Result<()> {
+        identifier: &EntryIdentifier,
+    ) -> Result<()> {
         if self.is_in_messagetable() {
             if self.id_stack.len() != 2 {
-                panic!("unexpected resource directory layout: len={}", self.id_stack.len());
+                return Err(Error::InvalidResourceLayout(format!(
+                    "message table has a depth of {}", self.id_stack.len())));
             }
-            let lang_id = match self.id_stack[1] {
-                EntryIdentifier::Id(x) => x,
-                _ => panic!("unexpected entry identifier")
+
+            // the data entry is identified by the language of the message table
+            let lang_id = match identifier {
+                EntryIdentifier::Id(x) => *x,
+                id => return Err(Error::InvalidResourceLayout(format!(
+                    "message table language is identified by {}, not by an id", id)))
             };
-            let iterator = MessagesIterator::new(self.pefile, lang_id.into(), entry)?;
-            self.iterators.push(iterator);
+            let table = MessageTable::new(self.pefile, lang_id.into(), entry)?;
+            self.tables.push(table);
         }
         Ok(())
     }
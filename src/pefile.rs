@@ -1,25 +1,46 @@
 use byteorder::{ByteOrder, LittleEndian};
 use memmap::MmapOptions;
 use num_traits::FromPrimitive;
-use num_traits::ToPrimitive;
 use std::fs::File;
-use std::io::{Error, ErrorKind};
-use std::path::PathBuf;
-use std::str;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use crate::data::{detect_layout, ImageData, ImageLayout};
+use crate::error::{Error, Result};
+use crate::utils::{bytes_at, cstring_from_slice};
 use crate::winnt::IMAGE_OPTIONAL_HEADER::*;
 use crate::winnt::*;
 use crate::msg::*;
+use crate::imports::*;
+use crate::exports::*;
+use crate::relocations::*;
+use crate::mapping::*;
+use crate::wevt::Manifest;
+use crate::resources::*;
 use from_bytes::*;
 
+/// maximum nesting of resource directories. Resource trees normally have three levels
+/// (type, name and language), everything below this limit is considered to be malformed.
+const MAX_RESOURCE_DEPTH: usize = 8;
+
+/// maximum number of resource directory entries which are visited. Directories may be
+/// referenced by several entries, so that a small resource section can describe a huge tree.
+const MAX_RESOURCE_ENTRIES: usize = 0x10_0000;
+
 #[allow(dead_code)]
 pub struct PEFile {
-    filename: PathBuf,
-    mmap: memmap::Mmap,
+    filename: Option<PathBuf>,
+    data: ImageData,
+    layout: ImageLayout,
     image_dos_header: IMAGE_DOS_HEADER,
     image_file_header: IMAGE_FILE_HEADER,
     image_optional_header: Option<IMAGE_OPTIONAL_HEADER>,
     directories: [Option<IMAGE_DATA_DIRECTORY>;16],
     sections: Vec<IMAGE_SECTION_HEADER>,
+
+    /// language, offset and number of blocks of all MESSAGE_TABLE resources,
+    /// so that the resource tree is walked only once
+    message_tables: OnceLock<Vec<(u32, usize, u32)>>,
 }
 
 /// represents a Portable Executable file
@@ -28,49 +49,110 @@ pub struct PEFile {
 /// ```
 /// use libpefile::*;
 /// use std::path::PathBuf;
-/// # fn main() -> std::io::Result<()> {
+/// # fn main() -> Result<()> {
 /// let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
 /// let dll_file = PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir));
 /// let pefile = PEFile::new(dll_file)?;
 /// 
 /// # Ok(())
 /// # }
+/// ```
+/// 
+/// PE images which are already in memory can be parsed as well:
+/// 
+/// ```
+/// use libpefile::*;
+/// # fn main() -> Result<()> {
+/// # let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+/// # let dll_file = format!("{}/samples/msaudite.dll", manifest_dir);
+/// let buffer: Vec<u8> = std::fs::read(dll_file)?;
+/// let pefile = PEFile::from_data(buffer)?;
+/// assert_eq!(pefile.sections().len(), 2);
+/// # Ok(())
+/// # }
+/// ```
 impl PEFile {
     /// parses a portable executable file into an internal data structure
-    pub fn new(filename: PathBuf) -> std::io::Result<PEFile> {
-        let mut offset = 0;
+    pub fn new(filename: PathBuf) -> Result<PEFile> {
+        Self::new_with_layout(filename, ImageLayout::File)
+    }
+
+    /// parses a portable executable file, whose sections are arranged as specified by `layout`.
+    ///
+    /// Use [ImageLayout::Memory] for modules which have been dumped from a process,
+    /// or [ImageLayout::Detect] if the layout is unknown.
+    pub fn new_with_layout(filename: PathBuf, layout: ImageLayout) -> Result<PEFile> {
         let file = File::open(&filename)?;
         let mmap = unsafe { MmapOptions::new().map(&file)? };
+        Self::parse(Some(filename), ImageData::Mapped(mmap), layout)
+    }
+
+    /// parses a portable executable which is already in memory.
+    ///
+    /// `data` can be anything which can be converted into [ImageData], such as
+    /// `Vec<u8>`, `Arc<[u8]>` or `&[u8]` (which will be copied).
+    pub fn from_data<D: Into<ImageData>>(data: D) -> Result<PEFile> {
+        Self::from_data_with_layout(data, ImageLayout::File)
+    }
+
+    /// parses a portable executable which is already in memory,
+    /// and whose sections are arranged as specified by `layout`.
+    ///
+    /// # Example
+    /// ```
+    /// use libpefile::*;
+    /// # fn main() -> Result<()> {
+    /// # let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    /// # let dll_file = format!("{}/samples/msaudite.dll", manifest_dir);
+    /// let pefile = PEFile::new(dll_file.into())?;
+    /// let dump = PEFile::from_data_with_layout(pefile.map_image()?, ImageLayout::Detect)?;
+    /// assert_eq!(dump.layout(), ImageLayout::Memory);
+    /// assert_eq!(dump.resources()?, pefile.resources()?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_data_with_layout<D: Into<ImageData>>(data: D, layout: ImageLayout) -> Result<PEFile> {
+        Self::parse(None, data.into(), layout)
+    }
+
+    /// reads a portable executable from `reader`, starting at its current position
+    pub fn from_reader<R: Read + Seek>(reader: &mut R) -> Result<PEFile> {
+        let start = reader.stream_position()?;
+        let end = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(start))?;
 
-        let image_dos_header = IMAGE_DOS_HEADER::from_bytes(&mmap, offset)?;
+        let mut buffer = Vec::with_capacity(end.saturating_sub(start) as usize);
+        reader.read_to_end(&mut buffer)?;
+        Self::parse(None, ImageData::Owned(buffer), ImageLayout::File)
+    }
+
+    fn parse(filename: Option<PathBuf>, data: ImageData, layout: ImageLayout) -> Result<PEFile> {
+        let mut offset = 0;
+        let image = &data[..];
+
+        let image_dos_header = IMAGE_DOS_HEADER::from_bytes(image, offset)?;
 
         if image_dos_header.e_magic != LittleEndian::read_u16(b"MZ") {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                format!("illegal DOS magic: {:?}", &mmap[0..2]),
-            ));
+            return Err(Error::BadDosMagic(image_dos_header.e_magic));
         } else {
             log::debug!("DOS magic is ok");
         }
 
         let nt_magic_offset = image_dos_header.e_lfanew as usize;
-        let nt_magic = &mmap[nt_magic_offset..nt_magic_offset + 4];
+        let nt_magic = bytes_at(image, nt_magic_offset, 4)?;
         if nt_magic != [b'P', b'E', 0, 0] {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                format!("illegal NT magic: {:?}", nt_magic),
-            ));
+            return Err(Error::BadNtSignature([nt_magic[0], nt_magic[1], nt_magic[2], nt_magic[3]]));
         } else {
             log::debug!("NT magic is ok");
         }
-        offset = (image_dos_header.e_lfanew + 4) as usize;
+        offset = nt_magic_offset + 4;
         let nt_header_size = IMAGE_FILE_HEADER::packed_size();
         log::debug!(
             "searching extended header at 0x{:08x}, size = {}",
             offset,
             nt_header_size
         );
-        let image_file_header = IMAGE_FILE_HEADER::from_bytes(&mmap, offset)?;
+        let image_file_header = IMAGE_FILE_HEADER::from_bytes(image, offset)?;
         offset += nt_header_size;
 
         let optional_header_size = image_file_header.SizeOfOptionalHeader as usize;
@@ -79,23 +161,20 @@ impl PEFile {
         let image_optional_header = if optional_header_size == 0 {
             None
         } else {
-            let header_magic = &mmap[offset..offset + 2];
-            match FromPrimitive::from_u16(LittleEndian::read_u16(header_magic)) {
+            let header_magic = LittleEndian::read_u16(bytes_at(image, offset, 2)?);
+            match FromPrimitive::from_u16(header_magic) {
                 Some(IMAGE_NT_OPTIONAL_HEADER::IMAGE_NT_OPTIONAL_HDR32_MAGIC) => {
-                    let header = IMAGE_OPTIONAL_HEADER32::from_bytes(&mmap, offset)?;
+                    let header = IMAGE_OPTIONAL_HEADER32::from_bytes(image, offset)?;
                     offset += IMAGE_OPTIONAL_HEADER32::packed_size();
                     Some(x86(*header))
                 }
                 Some(IMAGE_NT_OPTIONAL_HEADER::IMAGE_NT_OPTIONAL_HDR64_MAGIC) => {
-                    let header = IMAGE_OPTIONAL_HEADER64::from_bytes(&mmap, offset)?;
+                    let header = IMAGE_OPTIONAL_HEADER64::from_bytes(image, offset)?;
                     offset += IMAGE_OPTIONAL_HEADER64::packed_size();
                     Some(AMD64(*header))
                 }
                 _ => {
-                    return Err(Error::new(
-                        ErrorKind::InvalidData,
-                        format!("illegal optional header magic: {:?}", header_magic),
-                    ));
+                    return Err(Error::UnknownOptionalHeaderMagic(header_magic));
                 }
             }
         };
@@ -107,8 +186,8 @@ impl PEFile {
         if let Some(oh) = &image_optional_header {
             let entry_count = oh.NumberOfRvaAndSizes() as usize;
             let entry_size = IMAGE_DATA_DIRECTORY::packed_size();
-            for idx in 0..entry_count {
-                let entry = IMAGE_DATA_DIRECTORY::from_bytes(&mmap, offset + (entry_size * idx))?;
+            for (idx, directory) in directories.iter_mut().enumerate().take(entry_count) {
+                let entry = IMAGE_DATA_DIRECTORY::from_bytes(image, offset + (entry_size * idx))?;
 
                 if entry.VirtualAddress != 0 {
                     log::debug!(
@@ -117,10 +196,10 @@ impl PEFile {
                         entry.VirtualAddress,
                         entry.Size
                     );
-                    directories[idx] = Some(*entry);
+                    *directory = Some(*entry);
                 } else {
                     log::debug!("DATA DIRECTORY {:02}: <EMPTY>", idx);
-                    directories[idx] = None;
+                    *directory = None;
                 }
             }
 
@@ -132,9 +211,9 @@ impl PEFile {
         let entry_size = IMAGE_SECTION_HEADER::packed_size();
         for idx in 0..image_file_header.NumberOfSections {
             let entry =
-                IMAGE_SECTION_HEADER::from_bytes(&mmap, offset + (entry_size * idx as usize))?;
+                IMAGE_SECTION_HEADER::from_bytes(image, offset + (entry_size * idx as usize))?;
 
-            let section_name = str::from_utf8(&entry.Name[..]).unwrap();
+            let section_name = entry.name();
             let virt_size = entry.Misc;
             let virt_addr = entry.VirtualAddress;
             let raw_offset = entry.PointerToRawData;
@@ -156,16 +235,42 @@ impl PEFile {
             sections.push(*entry);
         }
 
+        let layout = match layout {
+            ImageLayout::Detect => {
+                let size_of_image = image_optional_header
+                    .as_ref()
+                    .map(|oh| oh.SizeOfImage() as usize)
+                    .unwrap_or(usize::MAX);
+                detect_layout(image, size_of_image, &sections)
+            }
+            layout => layout,
+        };
+        log::debug!("image layout is {:?}", layout);
+
         let me = PEFile {
             filename,
-            mmap,
+            data,
+            layout,
             image_dos_header: *image_dos_header,
             image_file_header: *image_file_header,
             image_optional_header,
-            directories: directories,
+            directories,
             sections,
+            message_tables: OnceLock::new(),
         };
-        return Ok(me);
+        Ok(me)
+    }
+
+    /// returns the name of the file which has been parsed, if the image has been loaded from a file
+    pub fn filename(&self) -> Option<&Path> {
+        self.filename.as_deref()
+    }
+
+    /// returns how the sections are arranged in the data of this image.
+    ///
+    /// This is never [ImageLayout::Detect]
+    pub fn layout(&self) -> ImageLayout {
+        self.layout
     }
 
     /// returns a reference to the [IMAGE_DOS_HEADER] structure
@@ -184,9 +289,9 @@ impl PEFile {
     }
 
     /// retuns a reference to the array of [IMAGE_DATA_DIRECTORY] structures.
-    /// 
+    ///
     /// Keep in mind that the entries have predefined indices, as documented at [https://docs.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-image_optional_header32](https://docs.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-image_optional_header32):
-    /// 
+    ///
     /// |Index|Value|Meaning|
     /// |-----|-----|-------|
     /// |0|[IMAGE_DIRECTORY_ENTRY::IMAGE_DIRECTORY_ENTRY_EXPORT]|Export directory|
@@ -204,31 +309,72 @@ impl PEFile {
     /// |12|[IMAGE_DIRECTORY_ENTRY::IMAGE_DIRECTORY_ENTRY_IAT]|Import address table|
     /// |13|[IMAGE_DIRECTORY_ENTRY::IMAGE_DIRECTORY_ENTRY_DELAY_IMPORT]|Delay import table|
     /// |14|[IMAGE_DIRECTORY_ENTRY::IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR]|COM descriptor table|
-    /// 
+    ///
     /// To access a specific entry, you can use the [IMAGE_DIRECTORY_ENTRY] enum
     pub fn directories(&self) -> &[Option<IMAGE_DATA_DIRECTORY>;16] {
         &self.directories
     }
 
+    /// returns a reference to a specific [IMAGE_DATA_DIRECTORY], if the image contains it
+    pub fn directory(&self, entry: IMAGE_DIRECTORY_ENTRY) -> Option<&IMAGE_DATA_DIRECTORY> {
+        self.directories[entry as usize].as_ref()
+    }
+
+    /// returns the preferred address of the image, or 0 if there is no optional header
+    pub fn image_base(&self) -> u64 {
+        self.image_optional_header.as_ref().map(|oh| oh.ImageBase()).unwrap_or(0)
+    }
+
+    /// returns the size of the headers, which are mapped at RVA 0.
+    ///
+    /// The result is limited to the RVA of the first section,
+    /// because sections must not overlap with the headers.
+    fn size_of_headers(&self) -> usize {
+        let size_of_headers = self
+            .image_optional_header
+            .as_ref()
+            .map(|oh| oh.SizeOfHeaders() as usize)
+            .unwrap_or(0);
+        self.sections
+            .iter()
+            .map(|s| s.VirtualAddress as usize)
+            .fold(size_of_headers, usize::min)
+    }
+
+    /// returns `true` if this is a PE32+ image, which uses 64bit addresses
+    pub fn is_64bit(&self) -> bool {
+        matches!(self.image_optional_header, Some(AMD64(_)))
+    }
+
     /// returns a reference to a vector of [IMAGE_SECTION_HEADER] structures
     pub fn sections(&self) -> &Vec<IMAGE_SECTION_HEADER> {
         &self.sections
     }
 
     /// calculates the offset in the file for a given RVA
-    /// 
+    ///
+    /// RVAs which point into the headers (which are mapped at RVA 0) are returned unchanged.
+    /// The same applies to all RVAs if the image uses [ImageLayout::Memory].
+    ///
     /// see also: [https://docs.microsoft.com/en-us/windows/win32/debug/pe-format](https://docs.microsoft.com/en-us/windows/win32/debug/pe-format)
     pub fn get_raw_address(&self, rva: usize) -> Option<usize> {
         match self.sections.iter().find(|&x| {
-            (x.VirtualAddress as usize..(x.VirtualAddress + x.Misc) as usize).contains(&rva)
+            (x.VirtualAddress as usize..x.VirtualAddress as usize + x.Misc as usize).contains(&rva)
         }) {
+            None if rva < self.size_of_headers() => {
+                log::debug!("found rva {:08x} in headers", rva);
+                Some(rva)
+            }
             None => None,
             Some(sect) => {
                 log::debug!(
                     "found rva {:08x} in section {}",
                     rva,
-                    str::from_utf8(&sect.Name[..]).unwrap()
+                    sect.name()
                 );
+                if self.layout == ImageLayout::Memory {
+                    return Some(rva);
+                }
                 let raw_address =
                     rva - sect.VirtualAddress as usize + sect.PointerToRawData as usize;
                 log::debug!(
@@ -243,66 +389,447 @@ impl PEFile {
         }
     }
 
-    /// returns a byte slice which containts exactly the resources section
+    /// returns `length` bytes of the image, starting at the given RVA
+    pub fn bytes_at_rva(&self, rva: usize, length: usize) -> Result<&[u8]> {
+        let offset = self.get_raw_address(rva).ok_or(Error::RvaNotMapped(rva))?;
+        bytes_at(&self.data, offset, length)
+    }
+
+    /// reads a structure which is stored at the given RVA
+    pub(crate) fn struct_at_rva<T: StructFromBytes>(&self, rva: usize) -> Result<Box<T>> {
+        let offset = self.get_raw_address(rva).ok_or(Error::RvaNotMapped(rva))?;
+        Ok(T::from_bytes(&self.data, offset)?)
+    }
+
+    /// reads a NUL-terminated ASCII string which is stored at the given RVA
+    pub(crate) fn cstring_at_rva(&self, rva: usize) -> Result<String> {
+        let offset = self.get_raw_address(rva).ok_or(Error::RvaNotMapped(rva))?;
+        cstring_from_slice(&self.data, offset)
+    }
+
+    /// returns a byte slice which containts exactly the resources section,
+    /// or `None` if there is none or it cannot be read
     pub fn get_resources_section(&self) -> Option<&[u8]> {
-        let idx_resources =
-            ToPrimitive::to_usize(&IMAGE_DIRECTORY_ENTRY::IMAGE_DIRECTORY_ENTRY_RESOURCE).unwrap();
-        if let Some(entry) = &self.directories[idx_resources] {
-            if let Some(offset) = self.get_raw_address(entry.VirtualAddress as usize) {
+        self.resources().ok()
+    }
+
+    /// returns a byte slice which containts exactly the resources section and fails if there is none
+    pub fn resources(&self) -> Result<&[u8]> {
+        match self.directory(IMAGE_DIRECTORY_ENTRY::IMAGE_DIRECTORY_ENTRY_RESOURCE) {
+            None => Err(Error::MissingDirectory(IMAGE_DIRECTORY_ENTRY::IMAGE_DIRECTORY_ENTRY_RESOURCE)),
+            Some(entry) => {
                 log::debug!(
-                    "loading resources of size {} at 0x{:08x}",
+                    "loading resources of size {} at rva 0x{:08x}",
                     entry.Size,
-                    offset
+                    entry.VirtualAddress
                 );
 
                 // create slice to enforce bounds checking
-                return Some(&self.mmap[offset..offset + entry.Size as usize]);
+                self.bytes_at_rva(entry.VirtualAddress as usize, entry.Size as usize)
             }
         }
-        None
-    }
-
-    /// returns a byte slice which containts exactly the resources section and fails if there is none
-    pub fn resources(&self) -> &[u8] {
-        self.get_resources_section().unwrap()
     }
 
     /// returns a byte slice of the full image
     pub fn full_image(&self) -> &[u8] {
-        &self.mmap[..]
+        &self.data[..]
+    }
+
+    /// creates a buffer of `SizeOfImage` bytes, which contains the image
+    /// laid out like the Windows loader would do it: the headers are stored at offset 0,
+    /// and every section is stored at its RVA. Gaps are filled with zeroes.
+    ///
+    /// # Example
+    /// ```
+    /// use libpefile::*;
+    /// # use std::path::PathBuf;
+    /// # fn main() -> Result<()> {
+    /// # let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    /// # let dll_file = PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir));
+    /// # let pefile = PEFile::new(dll_file)?;
+    /// let image = pefile.map_image()?;
+    /// let resources = pefile.directory(IMAGE_DIRECTORY_ENTRY::IMAGE_DIRECTORY_ENTRY_RESOURCE).unwrap();
+    /// let rva = resources.VirtualAddress as usize;
+    /// assert_eq!(&image[rva..rva + resources.Size as usize], pefile.resources()?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn map_image(&self) -> Result<Vec<u8>> {
+        map_image(self)
+    }
+
+    /// like [PEFile::map_image], but additionally applies all base relocations
+    /// as if the image was loaded at `image_base`
+    pub fn map_image_at(&self, image_base: u64) -> Result<Vec<u8>> {
+        let mut image = map_image(self)?;
+        self.apply_relocations(&mut image, image_base)?;
+        Ok(image)
     }
 
     /// returns an iterator over all items in the MESSAGE_TABLE
-    /// 
+    ///
     /// # Example
     /// ```
     /// use libpefile::*;
     /// # use std::path::PathBuf;
-    /// # fn main() -> std::io::Result<()> {
+    /// # fn main() -> Result<()> {
     /// # let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
     /// # let dll_file = PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir));
     /// # let pefile = PEFile::new(dll_file)?;
-    /// 
+    ///
     /// for msg in pefile.messages_iter()?.filter_map(|r| r.ok()) {
     ///     println!("{}: '{}'", msg.msg_id, msg.text);
     /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub fn messages_iter<'a>(&'a self) -> std::io::Result<impl Iterator<Item=std::io::Result<Message>> + 'a> {
+    pub fn messages_iter<'a>(&'a self) -> Result<impl Iterator<Item=Result<Message>> + 'a> {
         let mut visitor = MessageTableVisitor::new(self);
         self.visit_resource_tree(&mut visitor)?;
         Ok(visitor.into_iter())
     }
 
-    fn visit_resource_tree<V: ResourceDirectoryVisitor>(
+    /// returns an iterator over all messages in the MESSAGE_TABLE of the language `lang_id`.
+    ///
+    /// If there is no such message table, the iterator is empty.
+    ///
+    /// # Example
+    /// ```
+    /// use libpefile::*;
+    /// # use std::path::PathBuf;
+    /// # fn main() -> Result<()> {
+    /// # let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    /// # let dll_file = PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir));
+    /// # let pefile = PEFile::new(dll_file)?;
+    /// for lang_id in pefile.message_languages()? {
+    ///     println!("messages in {}:", LangId::from(lang_id));
+    ///     for msg in pefile.messages_iter_for_language(lang_id)?.filter_map(|r| r.ok()) {
+    ///         println!("{}: '{}'", msg.msg_id, msg.text);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn messages_iter_for_language(&self, lang_id: u32) -> Result<impl Iterator<Item=Result<Message>> + '_> {
+        Ok(self
+            .message_tables()?
+            .into_iter()
+            .filter(move |table| table.lang_id() == lang_id)
+            .flat_map(|table| table.iter()))
+    }
+
+    /// returns the languages of all MESSAGE_TABLE resources, in the order in which they are stored
+    pub fn message_languages(&self) -> Result<Vec<u32>> {
+        let mut languages = Vec::new();
+        for table in self.message_tables()? {
+            if !languages.contains(&table.lang_id()) {
+                languages.push(table.lang_id());
+            }
+        }
+        Ok(languages)
+    }
+
+    /// returns the MESSAGE_TABLE resources of all languages. The resource tree
+    /// is searched only on the first call.
+    pub(crate) fn message_tables(&self) -> Result<Vec<MessageTable<'_>>> {
+        let locations = match self.message_tables.get() {
+            Some(locations) => locations,
+            None => {
+                let mut visitor = MessageTableVisitor::new(self);
+                self.visit_resource_tree(&mut visitor)?;
+                let locations = visitor
+                    .into_tables()
+                    .iter()
+                    .map(|table| (table.lang_id(), table.rde_offset(), table.number_of_blocks()))
+                    .collect();
+                self.message_tables.get_or_init(|| locations)
+            }
+        };
+        Ok(locations
+            .iter()
+            .map(|&(lang_id, rde_offset, number_of_blocks)| {
+                MessageTable::from_parts(self, lang_id, rde_offset, number_of_blocks)
+            })
+            .collect())
+    }
+
+    /// searches a single message in the MESSAGE_TABLE of the language `lang_id`.
+    ///
+    /// Returns `None` if there is no such message table or if it does not contain `msg_id`.
+    ///
+    /// # Example
+    /// ```
+    /// use libpefile::*;
+    /// # use std::path::PathBuf;
+    /// # fn main() -> Result<()> {
+    /// # let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    /// # let dll_file = PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir));
+    /// # let pefile = PEFile::new(dll_file)?;
+    /// let msg = pefile.message(2, 0x0409)?.unwrap();
+    /// assert_eq!(msg.text, "Logon/Logoff\r\n\0\0");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn message(&self, msg_id: u32, lang_id: u32) -> Result<Option<Message>> {
+        for table in self.message_tables()? {
+            if table.lang_id() == lang_id {
+                return table.message(msg_id);
+            }
+        }
+        Ok(None)
+    }
+
+    /// searches a single message, like `FormatMessage` does if no language is specified:
+    /// the languages are tried in the following order, until the message is found:
+    ///
+    ///  1. `lang_id`
+    ///  2. the primary language of `lang_id` with `SUBLANG_NEUTRAL`
+    ///  3. the primary language of `lang_id` with any other sublanguage
+    ///  4. `LANG_NEUTRAL`
+    ///  5. English (United States)
+    ///  6. any other language in the image
+    pub fn message_with_fallback(&self, msg_id: u32, lang_id: u32) -> Result<Option<Message>> {
+        find_message_with_fallback(&self.message_tables()?, msg_id, lang_id)
+    }
+
+    /// returns an iterator over all DLLs which are imported by this image
+    ///
+    /// # Example
+    /// ```
+    /// use libpefile::*;
+    /// # use std::path::PathBuf;
+    /// # fn main() -> Result<()> {
+    /// # let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    /// # let dll_file = PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir));
+    /// # let pefile = PEFile::new(dll_file)?;
+    ///
+    /// for dll in pefile.imports()? {
+    ///     let dll = dll?;
+    ///     for function in dll.functions {
+    ///         println!("{}!{} at 0x{:08x}", dll.name, function.symbol, function.iat_rva);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn imports(&self) -> Result<ImportsIterator<'_>> {
+        self.imports_iter(IMAGE_DIRECTORY_ENTRY::IMAGE_DIRECTORY_ENTRY_IMPORT)
+    }
+
+    /// returns an iterator over all DLLs which are delay-loaded by this image.
+    ///
+    /// Both the current form (which uses RVAs) and the legacy form
+    /// (which uses virtual addresses) of the delay load descriptors are supported.
+    pub fn delay_imports(&self) -> Result<ImportsIterator<'_, IMAGE_DELAYLOAD_DESCRIPTOR>> {
+        self.imports_iter(IMAGE_DIRECTORY_ENTRY::IMAGE_DIRECTORY_ENTRY_DELAY_IMPORT)
+    }
+
+    /// parses the bound import directory, which lists the DLLs (and their
+    /// timestamps) which have been used to prefill the import address table
+    pub fn bound_imports(&self) -> Result<Vec<BoundImportedDll>> {
+        match self.directory(IMAGE_DIRECTORY_ENTRY::IMAGE_DIRECTORY_ENTRY_BOUND_IMPORT) {
+            None => Ok(Vec::new()),
+            Some(entry) => {
+                let directory = self.bytes_at_rva(entry.VirtualAddress as usize, entry.Size as usize)?;
+                read_bound_imports(directory)
+            }
+        }
+    }
+
+    /// returns an iterator over the blocks of the base relocation directory
+    pub fn relocations(&self) -> Result<RelocationsIterator<'_>> {
+        let directory = match self.directory(IMAGE_DIRECTORY_ENTRY::IMAGE_DIRECTORY_ENTRY_BASERELOC) {
+            None => &[][..],
+            Some(entry) => self.bytes_at_rva(entry.VirtualAddress as usize, entry.Size as usize)?,
+        };
+        Ok(RelocationsIterator::new(self, directory))
+    }
+
+    /// applies all base relocations to `image`, so that it can be used at `new_base`
+    /// instead of at the preferred image base.
+    ///
+    /// `image` must be laid out like in memory, i.e. every byte must be stored at its RVA.
+    pub fn apply_relocations(&self, image: &mut [u8], new_base: u64) -> Result<()> {
+        let delta = new_base.wrapping_sub(self.image_base());
+        apply_relocations(image, self.relocations()?, delta)
+    }
+
+    fn imports_iter<D: ImportDescriptor>(&self, entry: IMAGE_DIRECTORY_ENTRY) -> Result<ImportsIterator<'_, D>> {
+        let rva = self.directory(entry).map(|d| d.VirtualAddress as usize);
+        if let Some(rva) = rva {
+            self.get_raw_address(rva).ok_or(Error::RvaNotMapped(rva))?;
+        }
+        Ok(ImportsIterator::new(self, rva))
+    }
+
+    /// parses the export directory, if there is one
+    ///
+    /// Functions which are forwarded to other DLLs have a `forwarder`
+    /// string like `NTDLL.RtlAllocateHeap`.
+    pub fn exports(&self) -> Result<Option<ExportDirectory>> {
+        match self.directory(IMAGE_DIRECTORY_ENTRY::IMAGE_DIRECTORY_ENTRY_EXPORT) {
+            None => Ok(None),
+            Some(directory) => Ok(Some(read_exports(self, directory)?)),
+        }
+    }
+
+    /// parses the `WEVT_TEMPLATE` resource, which contains the definitions of manifest-based
+    /// event providers. Returns `None` if there is no such resource.
+    pub fn wevt_manifest(&self) -> Result<Option<Manifest>> {
+        let tree = self.resources_tree()?;
+        let resource = tree.iter_type("WEVT_TEMPLATE").next();
+        match resource {
+            None => Ok(None),
+            Some(resource) => {
+                log::debug!("found WEVT_TEMPLATE {} ({})", resource.name, resource.language.identifier);
+                Ok(Some(Manifest::parse(resource.language.data.bytes(self)?)?))
+            }
+        }
+    }
+
+    /// parses the first `RT_VERSION` resource. Returns `None` if there is no such resource.
+    pub fn version_info(&self) -> Result<Option<VersionInfo>> {
+        let tree = self.resources_tree()?;
+        let resource = tree.iter_type(ResourceType::RT_VERSION).next();
+        match resource {
+            None => Ok(None),
+            Some(resource) => Ok(Some(VersionInfo::parse(resource.language.data.bytes(self)?)?)),
+        }
+    }
+
+    /// returns an iterator over the strings of all `RT_STRING` resources, in all languages.
+    ///
+    /// Every resource contains a bundle of 16 strings; the string with the index `i`
+    /// in the bundle named `n` has the id `(n - 1) * 16 + i`. Empty strings are skipped.
+    ///
+    /// # Example
+    /// ```
+    /// use libpefile::*;
+    /// # use std::path::PathBuf;
+    /// # fn main() -> Result<()> {
+    /// # let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    /// # let dll_file = PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir));
+    /// # let pefile = PEFile::new(dll_file)?;
+    /// for string in pefile.string_table()?.filter_map(|r| r.ok()) {
+    ///     println!("{} ({}): '{}'", string.id, string.language(), string.text);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn string_table(&self) -> Result<StringTableIterator<'_>> {
+        let tree = self.resources_tree()?;
+        let entries = tree.iter_type(ResourceType::RT_STRING).collect();
+        Ok(StringTableIterator::new(self, entries))
+    }
+
+    /// searches the string `id` in the `RT_STRING` resource of the language `lang_id`.
+    ///
+    /// Returns `None` if there is no such resource or if the string is empty.
+    pub fn string(&self, id: u16, lang_id: u32) -> Result<Option<ResourceString>> {
+        find_string(self, &self.resources_tree()?, id, lang_id)
+    }
+
+    /// returns all icon groups. Use [IconGroup::to_file] to create the `.ico` file of a group.
+    pub fn icon_groups(&self) -> Result<Vec<IconGroup>> {
+        icon_groups(self, IconKind::Icon)
+    }
+
+    /// returns all cursor groups. Use [IconGroup::to_file] to create the `.cur` file of a group.
+    pub fn cursor_groups(&self) -> Result<Vec<IconGroup>> {
+        icon_groups(self, IconKind::Cursor)
+    }
+
+    /// returns all bitmaps. Use [Bitmap::to_file] to create the `.bmp` file of a bitmap.
+    pub fn bitmaps(&self) -> Result<Vec<Bitmap>> {
+        bitmaps(self)
+    }
+
+    /// parses all dialog templates
+    pub fn dialogs(&self) -> Result<Vec<Dialog>> {
+        dialogs(self)
+    }
+
+    /// parses all menu templates
+    pub fn menus(&self) -> Result<Vec<Menu>> {
+        menus(self)
+    }
+
+    /// parses all accelerator tables
+    pub fn accelerator_tables(&self) -> Result<Vec<AcceleratorTable>> {
+        accelerator_tables(self)
+    }
+
+    /// parses the side-by-side assembly manifest. Returns `None` if there is no `RT_MANIFEST` resource.
+    pub fn manifest(&self) -> Result<Option<AssemblyManifest>> {
+        manifest(self)
+    }
+
+    /// parses the `MUI` resource, which is present in language neutral files and in `.mui` files.
+    /// Use [MuiResolver] to find the `.mui` file of a language neutral file.
+    pub fn mui_config(&self) -> Result<Option<MuiConfig>> {
+        let tree = self.resources_tree()?;
+        let resource = tree.iter_type("MUI").next();
+        match resource {
+            None => Ok(None),
+            Some(resource) => Ok(Some(MuiConfig::parse(resource.language.data.bytes(self)?)?)),
+        }
+    }
+
+    /// returns all fonts. Use [Font::bytes] to get the contents of the font file.
+    pub fn fonts(&self) -> Result<Vec<Font>> {
+        fonts(self)
+    }
+
+    /// parses the font directory, which describes the metrics of all fonts.
+    /// Returns `None` if there is no `RT_FONTDIR` resource.
+    pub fn font_directory(&self) -> Result<Option<Vec<FontDirEntry>>> {
+        font_directory(self)
+    }
+
+    /// returns all resources of the image, organized by type, name and language.
+    /// Returns an empty tree if there is no resource directory.
+    ///
+    /// ```
+    /// use libpefile::*;
+    /// # use std::path::PathBuf;
+    /// # let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    /// # path.push("samples");
+    /// # path.push("msaudite.dll");
+    /// let pefile = PEFile::new(path)?;
+    /// let tree = pefile.resources_tree()?;
+    /// let table = tree.find(ResourceType::RT_MESSAGETABLE, 1, Some(0x0409)).unwrap();
+    /// assert!(table.bytes(&pefile)?.len() > 0);
+    /// # Ok::<(), libpefile::Error>(())
+    /// ```
+    pub fn resources_tree(&self) -> Result<ResourceTree> {
+        let mut visitor = ResourceTreeVisitor::default();
+        self.visit_resource_tree(&mut visitor)?;
+        Ok(visitor.into_tree())
+    }
+
+    /// walks through the resource directory, and passes every directory and data entry to `visitor`.
+    /// Does nothing if there is no resource directory.
+    pub fn visit_resource_tree<V: ResourceDirectoryVisitor>(
         &self,
         visitor: &mut V,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         visitor.init();
 
-        if let Some(resources) = self.get_resources_section() {
-            self.visit_directory(resources, visitor, 0, EntryIdentifier::NoIdentifier)?;
+        match self.resources() {
+            Ok(resources) => {
+                let mut path = Vec::new();
+                let mut visited_entries = 0;
+                self.visit_directory(
+                    resources,
+                    visitor,
+                    0,
+                    EntryIdentifier::NoIdentifier,
+                    &mut path,
+                    &mut visited_entries,
+                )?;
+            }
+            Err(Error::MissingDirectory(_)) => (),
+            Err(why) => return Err(why),
         }
 
         visitor.finalize();
@@ -315,18 +842,39 @@ impl PEFile {
         visitor: &mut V,
         offset: usize,
         identifier: EntryIdentifier,
-    ) -> std::io::Result<()> {
+        path: &mut Vec<usize>,
+        visited_entries: &mut usize,
+    ) -> Result<()> {
+        // a directory must not be its own parent, otherwise we would recurse forever
+        if path.contains(&offset) {
+            return Err(Error::ResourceLoop(offset));
+        }
+        if path.len() >= MAX_RESOURCE_DEPTH {
+            return Err(Error::InvalidResourceLayout(format!(
+                "resource directories are nested deeper than {} levels", MAX_RESOURCE_DEPTH)));
+        }
+        path.push(offset);
+
         let dir = IMAGE_RESOURCE_DIRECTORY::from_bytes(resources, offset)?;
         visitor.enter_resource_directory(&dir, &identifier)?;
 
-        let offset = offset + IMAGE_RESOURCE_DIRECTORY::packed_size();
+        let entries_offset = offset + IMAGE_RESOURCE_DIRECTORY::packed_size();
         let entry_size = IMAGE_RESOURCE_DIRECTORY_ENTRY::packed_size();
-        let count = (dir.NumberOfNamedEntries + dir.NumberOfIdEntries) as usize;
+        let count = dir.NumberOfNamedEntries as usize + dir.NumberOfIdEntries as usize;
         for idx in 0..count {
-            let entry_offset = offset + idx * entry_size;
-            self.visit_directory_entry(resources, visitor, entry_offset)?;
+            // directories which are referenced more than once are walked once per path
+            // leading to them, which grows exponentially with the depth of the tree
+            *visited_entries += 1;
+            if *visited_entries > MAX_RESOURCE_ENTRIES {
+                return Err(Error::InvalidResourceLayout(format!(
+                    "resource tree has more than {} entries", MAX_RESOURCE_ENTRIES)));
+            }
+            let entry_offset = entries_offset + idx * entry_size;
+            self.visit_directory_entry(resources, visitor, entry_offset, path, visited_entries)?;
         }
         visitor.leave_resource_directory(&dir, &identifier)?;
+
+        path.pop();
         Ok(())
     }
 
@@ -335,12 +883,15 @@ impl PEFile {
         resources: &[u8],
         visitor: &mut V,
         offset: usize,
-    ) -> std::io::Result<()> {
+        path: &mut Vec<usize>,
+        visited_entries: &mut usize,
+    ) -> Result<()> {
         let raw_entry = IMAGE_RESOURCE_DIRECTORY_ENTRY::from_bytes(resources, offset)?;
-        let identifier = raw_entry.parse_identifier(resources);
+        let identifier = raw_entry.parse_identifier(resources)?;
+        log::debug!("visiting resource directory entry {}", identifier);
         if (raw_entry.OffsetToData & 0x80000000) == 0x80000000 {
             let entry_offset = raw_entry.OffsetToData & 0x7fffffff;
-            self.visit_directory(resources, visitor, entry_offset as usize, identifier)?;
+            self.visit_directory(resources, visitor, entry_offset as usize, identifier, path, visited_entries)?;
         } else {
             let entry_offset = raw_entry.OffsetToData as usize;
             let raw_entry = IMAGE_RESOURCE_DATA_ENTRY::from_bytes(resources, entry_offset)?;
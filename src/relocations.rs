@@ -0,0 +1,246 @@
+use byteorder::{ByteOrder, LittleEndian};
+use from_bytes::*;
+use crate::error::{Error, Result};
+use crate::pefile::PEFile;
+use crate::utils::bytes_at;
+use crate::winnt::*;
+
+/// the way in which a base relocation modifies the image.
+///
+/// The meaning of some relocation types depends on the machine type of the image.
+/// ARM64 images only use [RelocationType::Dir64] and [RelocationType::Absolute].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelocationType {
+    /// padding, which does not modify the image
+    Absolute,
+
+    /// adds the high 16 bits of the delta to a 16 bit field
+    High,
+
+    /// adds the low 16 bits of the delta to a 16 bit field
+    Low,
+
+    /// adds the delta to a 32 bit field
+    HighLow,
+
+    /// adds the delta to a 32 bit value, whose high 16 bits are stored in the image
+    /// and whose low 16 bits are contained in the relocation entry which follows
+    HighAdj(u16),
+
+    /// adds the delta to a 32 bit address, which is encoded in a MOVW/MOVT pair of ARM instructions
+    ArmMov32,
+
+    /// adds the delta to a 32 bit address, which is encoded in a MOVW/MOVT pair of Thumb-2 instructions
+    ThumbMov32,
+
+    /// adds the delta to a 64 bit field
+    Dir64,
+
+    /// a relocation type which is not supported by this library
+    Unsupported(u8),
+}
+
+/// a single base relocation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Relocation {
+    /// RVA of the field which must be modified
+    pub rva: u32,
+    pub kind: RelocationType,
+}
+
+/// all relocations which apply to a single page of the image
+pub struct RelocationBlock {
+    pub header: IMAGE_BASE_RELOCATION,
+    pub relocations: Vec<Relocation>,
+}
+
+/// iterates over the blocks of the base relocation directory
+pub struct RelocationsIterator<'pefile> {
+    pefile: &'pefile PEFile,
+
+    /// contents of the base relocation directory
+    directory: &'pefile [u8],
+    offset: usize,
+}
+
+impl<'pefile> RelocationsIterator<'pefile> {
+    pub fn new(pefile: &'pefile PEFile, directory: &'pefile [u8]) -> Self {
+        Self {
+            pefile,
+            directory,
+            offset: 0,
+        }
+    }
+
+    pub fn do_next(&mut self) -> Result<Option<RelocationBlock>> {
+        if self.offset + IMAGE_BASE_RELOCATION::packed_size() > self.directory.len() {
+            return Ok(None);
+        }
+
+        let header = IMAGE_BASE_RELOCATION::from_bytes(self.directory, self.offset)?;
+        let header_size = IMAGE_BASE_RELOCATION::packed_size();
+        if (header.SizeOfBlock as usize) < header_size {
+            return Err(Error::InvalidData {
+                offset: self.offset,
+                reason: format!("size of relocation block is too small: {}", header.SizeOfBlock),
+            });
+        }
+        let entries = bytes_at(
+            self.directory,
+            self.offset + header_size,
+            header.SizeOfBlock as usize - header_size,
+        )?;
+        self.offset += header.SizeOfBlock as usize;
+
+        let is_arm = self.pefile.image_file_header().is_arm();
+        let mut entries = entries.chunks_exact(2).map(LittleEndian::read_u16);
+        let mut relocations = Vec::new();
+        while let Some(entry) = entries.next() {
+            let kind = match (entry >> 12) as u8 {
+                IMAGE_REL_BASED_ABSOLUTE => RelocationType::Absolute,
+                IMAGE_REL_BASED_HIGH => RelocationType::High,
+                IMAGE_REL_BASED_LOW => RelocationType::Low,
+                IMAGE_REL_BASED_HIGHLOW => RelocationType::HighLow,
+                IMAGE_REL_BASED_HIGHADJ => match entries.next() {
+                    Some(low) => RelocationType::HighAdj(low),
+                    None => {
+                        return Err(Error::InvalidData {
+                            offset: self.offset,
+                            reason: "HIGHADJ relocation without parameter".to_string(),
+                        })
+                    }
+                },
+                IMAGE_REL_BASED_ARM_MOV32 if is_arm => RelocationType::ArmMov32,
+                IMAGE_REL_BASED_THUMB_MOV32 if is_arm => RelocationType::ThumbMov32,
+                IMAGE_REL_BASED_DIR64 => RelocationType::Dir64,
+                kind => RelocationType::Unsupported(kind),
+            };
+            relocations.push(Relocation {
+                rva: header.VirtualAddress.wrapping_add((entry & 0x0fff) as u32),
+                kind,
+            });
+        }
+
+        Ok(Some(RelocationBlock {
+            header: *header,
+            relocations,
+        }))
+    }
+}
+
+impl<'pefile> Iterator for RelocationsIterator<'pefile> {
+    type Item = Result<RelocationBlock>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.do_next();
+
+        // we cannot know where the next block starts if this one is broken
+        if result.is_err() {
+            self.offset = self.directory.len();
+        }
+        result.transpose()
+    }
+}
+
+/// returns the 16 bit immediate value of a MOVW or MOVT instruction in ARM mode
+fn arm_imm16(instruction: u32) -> u32 {
+    ((instruction >> 4) & 0xf000) | (instruction & 0x0fff)
+}
+
+fn set_arm_imm16(instruction: u32, imm16: u32) -> u32 {
+    (instruction & 0xfff0_f000) | ((imm16 & 0xf000) << 4) | (imm16 & 0x0fff)
+}
+
+/// returns the 16 bit immediate value of a MOVW or MOVT instruction in Thumb-2 mode.
+/// The instruction is read as a little endian 32 bit value, so that the first halfword
+/// is contained in the lower 16 bits.
+fn thumb_imm16(instruction: u32) -> u32 {
+    ((instruction << 1) & 0x0800)
+        | ((instruction << 12) & 0xf000)
+        | ((instruction >> 20) & 0x0700)
+        | ((instruction >> 16) & 0x00ff)
+}
+
+fn set_thumb_imm16(instruction: u32, imm16: u32) -> u32 {
+    (instruction & 0x8f00_fbf0)
+        | ((imm16 >> 1) & 0x0400)
+        | ((imm16 >> 12) & 0x000f)
+        | ((imm16 << 20) & 0x7000_0000)
+        | ((imm16 << 16) & 0x00ff_0000)
+}
+
+/// applies a MOVW/MOVT relocation, using `get_imm16` and `set_imm16` to decode and encode the instructions
+fn relocate_mov32(
+    field: &mut [u8],
+    delta: u64,
+    get_imm16: fn(u32) -> u32,
+    set_imm16: fn(u32, u32) -> u32,
+) {
+    let movw = LittleEndian::read_u32(&field[0..4]);
+    let movt = LittleEndian::read_u32(&field[4..8]);
+    let address = (get_imm16(movw) | (get_imm16(movt) << 16)).wrapping_add(delta as u32);
+    LittleEndian::write_u32(&mut field[0..4], set_imm16(movw, address & 0xffff));
+    LittleEndian::write_u32(&mut field[4..8], set_imm16(movt, address >> 16));
+}
+
+/// returns the field of `size` bytes at `rva`, which is to be modified by a relocation
+fn field_at(image: &mut [u8], rva: usize, size: usize) -> Result<&mut [u8]> {
+    rva.checked_add(size)
+        .and_then(move |end| image.get_mut(rva..end))
+        .ok_or(Error::RvaNotMapped(rva))
+}
+
+/// modifies `image` (which must be laid out like in memory) by adding `delta` to all relocated fields
+pub(crate) fn apply_relocations<I>(image: &mut [u8], blocks: I, delta: u64) -> Result<()>
+where
+    I: Iterator<Item = Result<RelocationBlock>>,
+{
+    for block in blocks {
+        for relocation in block?.relocations {
+            let rva = relocation.rva as usize;
+            match relocation.kind {
+                RelocationType::Absolute => (),
+                RelocationType::High => {
+                    let field = field_at(image, rva, 2)?;
+                    let value = LittleEndian::read_u16(field).wrapping_add((delta >> 16) as u16);
+                    LittleEndian::write_u16(field, value);
+                }
+                RelocationType::Low => {
+                    let field = field_at(image, rva, 2)?;
+                    let value = LittleEndian::read_u16(field).wrapping_add(delta as u16);
+                    LittleEndian::write_u16(field, value);
+                }
+                RelocationType::HighAdj(low) => {
+                    let field = field_at(image, rva, 2)?;
+                    let value = ((LittleEndian::read_u16(field) as u32) << 16)
+                        .wrapping_add(low as i16 as i32 as u32)
+                        .wrapping_add(delta as u32)
+                        .wrapping_add(0x8000);
+                    LittleEndian::write_u16(field, (value >> 16) as u16);
+                }
+                RelocationType::HighLow => {
+                    let field = field_at(image, rva, 4)?;
+                    let value = LittleEndian::read_u32(field).wrapping_add(delta as u32);
+                    LittleEndian::write_u32(field, value);
+                }
+                RelocationType::Dir64 => {
+                    let field = field_at(image, rva, 8)?;
+                    let value = LittleEndian::read_u64(field).wrapping_add(delta);
+                    LittleEndian::write_u64(field, value);
+                }
+                RelocationType::ArmMov32 => {
+                    relocate_mov32(field_at(image, rva, 8)?, delta, arm_imm16, set_arm_imm16)
+                }
+                RelocationType::ThumbMov32 => {
+                    relocate_mov32(field_at(image, rva, 8)?, delta, thumb_imm16, set_thumb_imm16)
+                }
+                RelocationType::Unsupported(kind) => {
+                    return Err(Error::InvalidData {
+                        offset: rva,
+                        reason: format!("unsupported relocation type {}", kind),
+                    })
+                }
+            }
+        }
+    }
+    Ok(())
+}
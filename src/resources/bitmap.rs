@@ -0,0 +1,107 @@
+use byteorder::{ByteOrder, LittleEndian, WriteBytesExt};
+use from_bytes::*;
+use crate::error::{Error, Result};
+use crate::pefile::PEFile;
+use crate::resources::{ResourceData, ResourceType};
+use crate::utils::bytes_at;
+use crate::winnt::*;
+
+const BITMAPFILEHEADER_SIZE: u32 = 14;
+
+/// an `RT_BITMAP` resource
+#[derive(Debug, Clone)]
+pub struct Bitmap {
+    pub name: EntryIdentifier,
+    pub lang_id: Option<u16>,
+    pub data: ResourceData,
+}
+
+impl Bitmap {
+    /// returns the contents of a `.bmp` file which contains this bitmap
+    ///
+    /// # Example
+    /// ```no_run
+    /// use libpefile::*;
+    /// # fn main() -> std::io::Result<()> {
+    /// let pefile = PEFile::new(std::path::PathBuf::from("sample.exe"))?;
+    /// for bitmap in pefile.bitmaps()? {
+    ///     std::fs::write(format!("{}.bmp", bitmap.name), bitmap.to_file(&pefile)?)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_file(&self, pefile: &PEFile) -> Result<Vec<u8>> {
+        dib_to_bmp(self.data.bytes(pefile)?)
+    }
+}
+
+/// returns the offset of the pixels in a device independent bitmap, which starts with
+/// one of the `BITMAP*HEADER` structures, followed by optional color masks and the palette
+fn offset_of_pixels(dib: &[u8]) -> Result<u32> {
+    let header_size = LittleEndian::read_u32(bytes_at(dib, 0, 4)?);
+    if header_size == BITMAPCOREHEADER::packed_size() as u32 {
+        let header = BITMAPCOREHEADER::from_bytes(dib, 0)?;
+        let colors = if header.bcBitCount <= 8 { 1u32 << header.bcBitCount } else { 0 };
+        return Ok(header_size + colors * 3);
+    }
+
+    let header = BITMAPINFOHEADER::from_bytes(dib, 0)?;
+    let masks = match header_size {
+        BITMAPINFOHEADER_SIZE => match header.biCompression {
+            BI_BITFIELDS => 3 * 4,
+            BI_ALPHABITFIELDS => 4 * 4,
+            _ => 0,
+        },
+        // the masks are part of the header
+        BITMAPV2INFOHEADER_SIZE | BITMAPV3INFOHEADER_SIZE | OS2BITMAPHEADER2_SIZE
+        | BITMAPV4HEADER_SIZE | BITMAPV5HEADER_SIZE => 0,
+        _ => {
+            return Err(Error::InvalidData {
+                offset: 0,
+                reason: format!("unknown bitmap header size {}", header_size),
+            })
+        }
+    };
+    let colors = match header.biClrUsed {
+        0 if (1..=8).contains(&header.biBitCount) => 1u32 << header.biBitCount,
+        colors => colors,
+    };
+    colors
+        .checked_mul(4)
+        .and_then(|palette| palette.checked_add(header_size + masks))
+        .ok_or_else(|| Error::InvalidData {
+            offset: 32,
+            reason: format!("invalid number of colors: {}", header.biClrUsed),
+        })
+}
+
+/// prepends a `BITMAPFILEHEADER` to the contents of an `RT_BITMAP` resource, so that it
+/// can be stored as `.bmp` file
+pub fn dib_to_bmp(dib: &[u8]) -> Result<Vec<u8>> {
+    let offset = offset_of_pixels(dib)?;
+    if offset as usize > dib.len() {
+        return Err(Error::Truncated { offset: 0, needed: offset as usize });
+    }
+
+    let mut bmp = Vec::with_capacity(BITMAPFILEHEADER_SIZE as usize + dib.len());
+    bmp.extend_from_slice(b"BM");
+    bmp.write_u32::<LittleEndian>(BITMAPFILEHEADER_SIZE + dib.len() as u32)?;
+    bmp.write_u16::<LittleEndian>(0)?;
+    bmp.write_u16::<LittleEndian>(0)?;
+    bmp.write_u32::<LittleEndian>(BITMAPFILEHEADER_SIZE + offset)?;
+    bmp.extend_from_slice(dib);
+    Ok(bmp)
+}
+
+/// returns all `RT_BITMAP` resources
+pub(crate) fn bitmaps(pefile: &PEFile) -> Result<Vec<Bitmap>> {
+    let tree = pefile.resources_tree()?;
+    Ok(tree
+        .iter_type(ResourceType::RT_BITMAP)
+        .map(|entry| Bitmap {
+            name: entry.name.clone(),
+            lang_id: entry.language.lang_id(),
+            data: entry.language.data,
+        })
+        .collect())
+}
@@ -0,0 +1,208 @@
+use crate::error::{Error, Result};
+use crate::pefile::PEFile;
+use crate::resources::{NameOrOrdinal, ResourceType, TemplateReader};
+use crate::winnt::*;
+
+/// the font of a dialog, which is only present if the style contains `DS_SETFONT`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialogFont {
+    pub point_size: u16,
+
+    /// only stored in extended templates
+    pub weight: u16,
+    pub italic: bool,
+    pub charset: u8,
+    pub typeface: String,
+}
+
+/// a control of a dialog
+#[derive(Debug, Clone)]
+pub struct DialogControl {
+    pub id: u32,
+    pub style: u32,
+    pub ex_style: u32,
+
+    /// only stored in extended templates
+    pub help_id: u32,
+    pub x: i16,
+    pub y: i16,
+    pub cx: i16,
+    pub cy: i16,
+    pub class: NameOrOrdinal,
+    pub text: NameOrOrdinal,
+    pub creation_data: Vec<u8>,
+}
+
+impl DialogControl {
+    /// returns the name of the window class, resolving the ordinals of the predefined classes
+    pub fn class_name(&self) -> Option<String> {
+        match &self.class {
+            NameOrOrdinal::None => None,
+            NameOrOrdinal::Name(name) => Some(name.clone()),
+            NameOrOrdinal::Ordinal(ordinal) => Some(match ordinal {
+                0x0080 => "Button".to_string(),
+                0x0081 => "Edit".to_string(),
+                0x0082 => "Static".to_string(),
+                0x0083 => "ListBox".to_string(),
+                0x0084 => "ScrollBar".to_string(),
+                0x0085 => "ComboBox".to_string(),
+                ordinal => format!("#{}", ordinal),
+            }),
+        }
+    }
+}
+
+/// an `RT_DIALOG` resource, which is stored either as `DLGTEMPLATE` or as `DLGTEMPLATEEX`
+#[derive(Debug, Clone)]
+pub struct Dialog {
+    pub name: EntryIdentifier,
+    pub lang_id: Option<u16>,
+
+    /// `true` if the dialog is stored as `DLGTEMPLATEEX`
+    pub extended: bool,
+    pub style: u32,
+    pub ex_style: u32,
+
+    /// only stored in extended templates
+    pub help_id: u32,
+    pub x: i16,
+    pub y: i16,
+    pub cx: i16,
+    pub cy: i16,
+    pub menu: NameOrOrdinal,
+    pub class: NameOrOrdinal,
+    pub caption: String,
+    pub font: Option<DialogFont>,
+    pub controls: Vec<DialogControl>,
+}
+
+impl Dialog {
+    pub(crate) fn parse(name: EntryIdentifier, lang_id: Option<u16>, data: &[u8]) -> Result<Self> {
+        let mut reader = TemplateReader::new(data);
+        let is_extended = data.len() >= 4 && data[0..4] == [0x01, 0x00, 0xff, 0xff];
+
+        let (mut dialog, count) = if is_extended {
+            let template = reader.read::<DLGTEMPLATEEX>()?;
+            let dialog = Self::new(name, lang_id, true, template.style, template.exStyle, template.helpID,
+                [template.x, template.y, template.cx, template.cy]);
+            (dialog, template.cDlgItems)
+        } else {
+            let template = reader.read::<DLGTEMPLATE>()?;
+            let dialog = Self::new(name, lang_id, false, template.style, template.dwExtendedStyle, 0,
+                [template.x, template.y, template.cx, template.cy]);
+            (dialog, template.cdit)
+        };
+
+        dialog.menu = reader.name_or_ordinal()?;
+        dialog.class = reader.name_or_ordinal()?;
+        dialog.caption = reader.string()?;
+        if dialog.style & DS_SETFONT != 0 {
+            let point_size = reader.u16()?;
+            let (weight, italic, charset) = if is_extended {
+                (reader.u16()?, reader.u8()? != 0, reader.u8()?)
+            } else {
+                (0, false, 0)
+            };
+            dialog.font = Some(DialogFont {
+                point_size,
+                weight,
+                italic,
+                charset,
+                typeface: reader.string()?,
+            });
+        }
+
+        for _ in 0..count {
+            reader.align(4);
+            let control = if is_extended {
+                Self::parse_control_ex(&mut reader)?
+            } else {
+                Self::parse_control(&mut reader)?
+            };
+            dialog.controls.push(control);
+        }
+        Ok(dialog)
+    }
+
+    fn new(name: EntryIdentifier, lang_id: Option<u16>, extended: bool,
+           style: u32, ex_style: u32, help_id: u32, [x, y, cx, cy]: [i16; 4]) -> Self {
+        Self {
+            name,
+            lang_id,
+            extended,
+            style,
+            ex_style,
+            help_id,
+            x,
+            y,
+            cx,
+            cy,
+            menu: NameOrOrdinal::None,
+            class: NameOrOrdinal::None,
+            caption: String::new(),
+            font: None,
+            controls: Vec::new(),
+        }
+    }
+
+    fn parse_control(reader: &mut TemplateReader<'_>) -> Result<DialogControl> {
+        let item = reader.read::<DLGITEMTEMPLATE>()?;
+        let class = reader.name_or_ordinal()?;
+        let text = reader.name_or_ordinal()?;
+
+        // the size of the creation data includes the size field itself
+        let offset = reader.offset();
+        let creation_data = match reader.u16()? as usize {
+            0 => Vec::new(),
+            1 => return Err(Error::InvalidData {
+                offset,
+                reason: "size of creation data is too small".to_string(),
+            }),
+            size => reader.bytes(size - 2)?.to_vec(),
+        };
+        Ok(DialogControl {
+            id: item.id as u32,
+            style: item.style,
+            ex_style: item.dwExtendedStyle,
+            help_id: 0,
+            x: item.x,
+            y: item.y,
+            cx: item.cx,
+            cy: item.cy,
+            class,
+            text,
+            creation_data,
+        })
+    }
+
+    fn parse_control_ex(reader: &mut TemplateReader<'_>) -> Result<DialogControl> {
+        let item = reader.read::<DLGITEMTEMPLATEEX>()?;
+        let class = reader.name_or_ordinal()?;
+        let text = reader.name_or_ordinal()?;
+        let size = reader.u16()? as usize;
+        let creation_data = reader.bytes(size)?.to_vec();
+        Ok(DialogControl {
+            id: item.id,
+            style: item.style,
+            ex_style: item.exStyle,
+            help_id: item.helpID,
+            x: item.x,
+            y: item.y,
+            cx: item.cx,
+            cy: item.cy,
+            class,
+            text,
+            creation_data,
+        })
+    }
+}
+
+/// parses all `RT_DIALOG` resources
+pub(crate) fn dialogs(pefile: &PEFile) -> Result<Vec<Dialog>> {
+    let tree = pefile.resources_tree()?;
+    tree.iter_type(ResourceType::RT_DIALOG)
+        .map(|entry| {
+            Dialog::parse(entry.name.clone(), entry.language.lang_id(), entry.language.data.bytes(pefile)?)
+        })
+        .collect()
+}
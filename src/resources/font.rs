@@ -0,0 +1,195 @@
+use byteorder::{ByteOrder, LittleEndian};
+use encoding_rs::WINDOWS_1252;
+use from_bytes::*;
+use crate::error::{Error, Result};
+use crate::pefile::PEFile;
+use crate::resources::{ResourceData, ResourceType};
+use crate::utils::bytes_at;
+use crate::winnt::*;
+
+/// the file format of an `RT_FONT` resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontFormat {
+    /// a Windows bitmap or vector font (`.fnt`)
+    Fnt,
+    TrueType,
+
+    /// an OpenType font with CFF outlines
+    OpenType,
+    TrueTypeCollection,
+    Unknown,
+}
+
+impl FontFormat {
+    /// detects the format by looking at the beginning of a font resource
+    pub fn detect(data: &[u8]) -> Self {
+        if data.len() < 6 {
+            return FontFormat::Unknown;
+        }
+        match &data[0..4] {
+            [0x00, 0x01, 0x00, 0x00] | b"true" => return FontFormat::TrueType,
+            b"OTTO" => return FontFormat::OpenType,
+            b"ttcf" => return FontFormat::TrueTypeCollection,
+            _ => (),
+        }
+
+        // FNT files start with their version and their size
+        let version = LittleEndian::read_u16(&data[0..2]);
+        let size = LittleEndian::read_u32(&data[2..6]) as usize;
+        if matches!(version, 0x0100 | 0x0200 | 0x0300) && size <= data.len() {
+            return FontFormat::Fnt;
+        }
+        FontFormat::Unknown
+    }
+
+    /// returns the usual file name extension
+    pub fn extension(&self) -> &'static str {
+        match self {
+            FontFormat::Fnt => "fnt",
+            FontFormat::TrueType => "ttf",
+            FontFormat::OpenType => "otf",
+            FontFormat::TrueTypeCollection => "ttc",
+            FontFormat::Unknown => "bin",
+        }
+    }
+}
+
+/// an `RT_FONT` resource
+#[derive(Debug, Clone)]
+pub struct Font {
+    pub name: EntryIdentifier,
+    pub lang_id: Option<u16>,
+    pub data: ResourceData,
+    pub format: FontFormat,
+}
+
+impl Font {
+    /// returns the contents of the font file
+    pub fn bytes<'pefile>(&self, pefile: &'pefile PEFile) -> Result<&'pefile [u8]> {
+        self.data.bytes(pefile)
+    }
+}
+
+/// the metrics of a font, as stored in the `RT_FONTDIR` resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontDirEntry {
+    /// the name of the `RT_FONT` resource
+    pub ordinal: u16,
+    pub version: u16,
+    pub size: u32,
+    pub copyright: String,
+    pub font_type: u16,
+    pub points: u16,
+    pub vert_res: u16,
+    pub horiz_res: u16,
+    pub ascent: u16,
+    pub internal_leading: u16,
+    pub external_leading: u16,
+    pub italic: bool,
+    pub underline: bool,
+    pub strike_out: bool,
+    pub weight: u16,
+    pub charset: u8,
+    pub pix_width: u16,
+    pub pix_height: u16,
+    pub pitch_and_family: u8,
+    pub avg_width: u16,
+    pub max_width: u16,
+    pub first_char: u8,
+    pub last_char: u8,
+    pub default_char: u8,
+    pub break_char: u8,
+    pub width_bytes: u16,
+    pub device_name: String,
+    pub face_name: String,
+}
+
+/// decodes an ANSI string, which ends at the first NUL character or at the end of `data`
+fn ansi_string(data: &[u8]) -> String {
+    let length = data.iter().position(|&c| c == 0).unwrap_or(data.len());
+    WINDOWS_1252.decode_without_bom_handling(&data[..length]).0.to_string()
+}
+
+/// reads a NUL-terminated ANSI string and returns it together with its size, including the NUL
+fn ansi_string_at(data: &[u8], offset: usize) -> Result<(String, usize)> {
+    let bytes = data.get(offset..).ok_or(Error::Truncated { offset, needed: 1 })?;
+    match bytes.iter().position(|&c| c == 0) {
+        Some(length) => Ok((ansi_string(&bytes[..length]), length + 1)),
+        None => Err(Error::Truncated { offset, needed: bytes.len() + 1 }),
+    }
+}
+
+/// parses an `RT_FONTDIR` resource, which consists of the number of fonts and the font entries
+pub(crate) fn parse_font_directory(data: &[u8]) -> Result<Vec<FontDirEntry>> {
+    let count = LittleEndian::read_u16(bytes_at(data, 0, 2)?);
+    let mut offset = 2;
+    let mut entries = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let ordinal = LittleEndian::read_u16(bytes_at(data, offset, 2)?);
+        offset += 2;
+        let entry = FONTDIRENTRY::from_bytes(data, offset)?;
+        offset += FONTDIRENTRY::packed_size();
+        let (device_name, size) = ansi_string_at(data, offset)?;
+        offset += size;
+        let (face_name, size) = ansi_string_at(data, offset)?;
+        offset += size;
+
+        entries.push(FontDirEntry {
+            ordinal,
+            version: entry.dfVersion,
+            size: entry.dfSize,
+            copyright: ansi_string(&entry.dfCopyright),
+            font_type: entry.dfType,
+            points: entry.dfPoints,
+            vert_res: entry.dfVertRes,
+            horiz_res: entry.dfHorizRes,
+            ascent: entry.dfAscent,
+            internal_leading: entry.dfInternalLeading,
+            external_leading: entry.dfExternalLeading,
+            italic: entry.dfItalic != 0,
+            underline: entry.dfUnderline != 0,
+            strike_out: entry.dfStrikeOut != 0,
+            weight: entry.dfWeight,
+            charset: entry.dfCharSet,
+            pix_width: entry.dfPixWidth,
+            pix_height: entry.dfPixHeight,
+            pitch_and_family: entry.dfPitchAndFamily,
+            avg_width: entry.dfAvgWidth,
+            max_width: entry.dfMaxWidth,
+            first_char: entry.dfFirstChar,
+            last_char: entry.dfLastChar,
+            default_char: entry.dfDefaultChar,
+            break_char: entry.dfBreakChar,
+            width_bytes: entry.dfWidthBytes,
+            device_name,
+            face_name,
+        });
+    }
+    Ok(entries)
+}
+
+/// returns all `RT_FONT` resources
+pub(crate) fn fonts(pefile: &PEFile) -> Result<Vec<Font>> {
+    let tree = pefile.resources_tree()?;
+    tree.iter_type(ResourceType::RT_FONT)
+        .map(|entry| {
+            let data = entry.language.data;
+            Ok(Font {
+                name: entry.name.clone(),
+                lang_id: entry.language.lang_id(),
+                data,
+                format: FontFormat::detect(data.bytes(pefile)?),
+            })
+        })
+        .collect()
+}
+
+/// parses the first `RT_FONTDIR` resource
+pub(crate) fn font_directory(pefile: &PEFile) -> Result<Option<Vec<FontDirEntry>>> {
+    let tree = pefile.resources_tree()?;
+    let resource = tree.iter_type(ResourceType::RT_FONTDIR).next();
+    match resource {
+        None => Ok(None),
+        Some(resource) => Ok(Some(parse_font_directory(resource.language.data.bytes(pefile)?)?)),
+    }
+}
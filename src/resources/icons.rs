@@ -0,0 +1,190 @@
+use byteorder::{ByteOrder, LittleEndian, WriteBytesExt};
+use from_bytes::*;
+use crate::error::{Error, Result};
+use crate::pefile::PEFile;
+use crate::resources::{ResourceType, ResourceTree};
+use crate::winnt::*;
+
+/// distinguishes `RT_GROUP_ICON` from `RT_GROUP_CURSOR` resources
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IconKind {
+    Icon,
+    Cursor,
+}
+
+impl IconKind {
+    fn group_type(self) -> ResourceType {
+        match self {
+            IconKind::Icon => ResourceType::RT_GROUP_ICON,
+            IconKind::Cursor => ResourceType::RT_GROUP_CURSOR,
+        }
+    }
+
+    fn image_type(self) -> ResourceType {
+        match self {
+            IconKind::Icon => ResourceType::RT_ICON,
+            IconKind::Cursor => ResourceType::RT_CURSOR,
+        }
+    }
+}
+
+/// a single image of an icon or cursor group, which is stored in a separate
+/// `RT_ICON` or `RT_CURSOR` resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IconImage {
+    /// name of the `RT_ICON` or `RT_CURSOR` resource
+    pub id: u16,
+    pub width: u16,
+    pub height: u16,
+
+    /// number of colors in the palette, or 0 if there is no palette
+    pub color_count: u8,
+    pub planes: u16,
+    pub bit_count: u16,
+    pub bytes_in_res: u32,
+}
+
+/// an `RT_GROUP_ICON` or `RT_GROUP_CURSOR` resource, which combines several
+/// images of the same icon or cursor
+#[derive(Debug, Clone)]
+pub struct IconGroup {
+    pub kind: IconKind,
+    pub name: EntryIdentifier,
+    pub lang_id: Option<u16>,
+    pub images: Vec<IconImage>,
+}
+
+const ICONDIR_SIZE: usize = 6;
+const ICONDIRENTRY_SIZE: usize = 16;
+
+impl IconGroup {
+    pub(crate) fn parse(kind: IconKind, name: EntryIdentifier, lang_id: Option<u16>, data: &[u8]) -> Result<Self> {
+        let dir = GRPICONDIR::from_bytes(data, 0)?;
+        let expected_type = match kind {
+            IconKind::Icon => RES_ICON,
+            IconKind::Cursor => RES_CURSOR,
+        };
+        if dir.idType != expected_type {
+            return Err(Error::InvalidData {
+                offset: 2,
+                reason: format!("unexpected icon group type {} in {:?} group {}", dir.idType, kind, name),
+            });
+        }
+
+        let mut images = Vec::with_capacity(dir.idCount as usize);
+        let mut offset = GRPICONDIR::packed_size();
+        for _ in 0..dir.idCount {
+            let image = match kind {
+                IconKind::Icon => {
+                    let entry = GRPICONDIRENTRY::from_bytes(data, offset)?;
+                    offset += GRPICONDIRENTRY::packed_size();
+                    IconImage {
+                        id: entry.nId,
+                        width: if entry.bWidth == 0 { 256 } else { entry.bWidth as u16 },
+                        height: if entry.bHeight == 0 { 256 } else { entry.bHeight as u16 },
+                        color_count: entry.bColorCount,
+                        planes: entry.wPlanes,
+                        bit_count: entry.wBitCount,
+                        bytes_in_res: entry.dwBytesInRes,
+                    }
+                }
+                IconKind::Cursor => {
+                    let entry = GRPCURSORDIRENTRY::from_bytes(data, offset)?;
+                    offset += GRPCURSORDIRENTRY::packed_size();
+                    IconImage {
+                        id: entry.nId,
+                        width: entry.wWidth,
+                        // the height includes the AND mask
+                        height: entry.wHeight / 2,
+                        color_count: if entry.wBitCount < 8 { 1 << entry.wBitCount } else { 0 },
+                        planes: entry.wPlanes,
+                        bit_count: entry.wBitCount,
+                        bytes_in_res: entry.dwBytesInRes,
+                    }
+                }
+            };
+            images.push(image);
+        }
+        Ok(Self { kind, name, lang_id, images })
+    }
+
+    /// assembles the group and its images into the contents of an `.ico` or `.cur` file
+    ///
+    /// # Example
+    /// ```no_run
+    /// use libpefile::*;
+    /// # fn main() -> std::io::Result<()> {
+    /// let pefile = PEFile::new(std::path::PathBuf::from("sample.exe"))?;
+    /// if let Some(group) = pefile.icon_groups()?.first() {
+    ///     std::fs::write("sample.ico", group.to_file(&pefile)?)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_file(&self, pefile: &PEFile) -> Result<Vec<u8>> {
+        self.assemble(pefile, &pefile.resources_tree()?)
+    }
+
+    fn assemble(&self, pefile: &PEFile, tree: &ResourceTree) -> Result<Vec<u8>> {
+        let mut entries = Vec::new();
+        let mut images = Vec::new();
+        let mut image_offset = ICONDIR_SIZE + ICONDIRENTRY_SIZE * self.images.len();
+        for image in self.images.iter() {
+            let data = match tree.find(self.kind.image_type(), image.id, self.lang_id) {
+                Some(data) => data.bytes(pefile)?,
+                None => return Err(Error::InvalidResourceLayout(format!(
+                    "{:?} group {} refers to the missing image #{}", self.kind, self.name, image.id))),
+            };
+
+            // cursor images are preceded by the coordinates of their hotspot
+            let (planes_or_x, bit_count_or_y, data) = match self.kind {
+                IconKind::Icon => (image.planes, image.bit_count, data),
+                IconKind::Cursor => {
+                    if data.len() < 4 {
+                        return Err(Error::Truncated { offset: 0, needed: 4 });
+                    }
+                    (LittleEndian::read_u16(&data[0..2]), LittleEndian::read_u16(&data[2..4]), &data[4..])
+                }
+            };
+
+            entries.push(image.width as u8);    /* 256 is stored as 0 */
+            entries.push(image.height as u8);
+            entries.push(image.color_count);
+            entries.push(0);
+            entries.write_u16::<LittleEndian>(planes_or_x)?;
+            entries.write_u16::<LittleEndian>(bit_count_or_y)?;
+            entries.write_u32::<LittleEndian>(data.len() as u32)?;
+            entries.write_u32::<LittleEndian>(image_offset as u32)?;
+            image_offset += data.len();
+            images.push(data);
+        }
+
+        let mut file = Vec::with_capacity(image_offset);
+        file.write_u16::<LittleEndian>(0)?;
+        file.write_u16::<LittleEndian>(match self.kind {
+            IconKind::Icon => RES_ICON,
+            IconKind::Cursor => RES_CURSOR,
+        })?;
+        file.write_u16::<LittleEndian>(self.images.len() as u16)?;
+        file.extend_from_slice(&entries);
+        for image in images {
+            file.extend_from_slice(image);
+        }
+        Ok(file)
+    }
+}
+
+/// parses all `RT_GROUP_ICON` or `RT_GROUP_CURSOR` resources
+pub(crate) fn icon_groups(pefile: &PEFile, kind: IconKind) -> Result<Vec<IconGroup>> {
+    let tree = pefile.resources_tree()?;
+    tree.iter_type(kind.group_type())
+        .map(|entry| {
+            IconGroup::parse(
+                kind,
+                entry.name.clone(),
+                entry.language.lang_id(),
+                entry.language.data.bytes(pefile)?,
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,122 @@
+use encoding_rs::{Encoding, UTF_8};
+use crate::error::{Error, Result};
+use crate::pefile::PEFile;
+use crate::resources::ResourceType;
+use crate::winnt::EntryIdentifier;
+
+/// an assembly which is referenced by a `dependentAssembly` element
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DependentAssembly {
+    pub name: String,
+    pub version: Option<String>,
+    pub assembly_type: Option<String>,
+    pub processor_architecture: Option<String>,
+    pub public_key_token: Option<String>,
+    pub language: Option<String>,
+}
+
+/// a side-by-side assembly manifest, which is stored as `RT_MANIFEST` resource
+#[derive(Debug, Clone)]
+pub struct AssemblyManifest {
+    pub name: EntryIdentifier,
+    pub lang_id: Option<u16>,
+
+    /// the XML text, without byte order mark
+    pub xml: String,
+
+    /// `asInvoker`, `highestAvailable` or `requireAdministrator`
+    pub requested_execution_level: Option<String>,
+    pub ui_access: Option<bool>,
+    pub auto_elevate: Option<bool>,
+    pub dpi_aware: Option<String>,
+    pub dpi_awareness: Option<String>,
+
+    /// the GUIDs of the operating systems with which the application is compatible
+    pub supported_os: Vec<String>,
+    pub dependent_assemblies: Vec<DependentAssembly>,
+}
+
+impl AssemblyManifest {
+    pub(crate) fn parse(name: EntryIdentifier, lang_id: Option<u16>, data: &[u8]) -> Result<Self> {
+        let (encoding, bom_length) = Encoding::for_bom(data).unwrap_or((UTF_8, 0));
+        let (xml, _) = encoding.decode_without_bom_handling(&data[bom_length..]);
+
+        // manifests are often padded with spaces or NUL characters, which are not valid XML
+        let xml = xml.trim_end_matches(|c: char| c == '\0' || c.is_whitespace()).to_string();
+
+        let mut manifest = Self {
+            name,
+            lang_id,
+            xml: String::new(),
+            requested_execution_level: None,
+            ui_access: None,
+            auto_elevate: None,
+            dpi_aware: None,
+            dpi_awareness: None,
+            supported_os: Vec::new(),
+            dependent_assemblies: Vec::new(),
+        };
+
+        let document = roxmltree::Document::parse(&xml).map_err(|why| Error::InvalidData {
+            offset: 0,
+            reason: format!("invalid manifest: {}", why),
+        })?;
+
+        // elements are matched by their local name, because manifests use
+        // different prefixes and namespaces for the same elements
+        for node in document.descendants().filter(|n| n.is_element()) {
+            match node.tag_name().name() {
+                "requestedExecutionLevel" => {
+                    manifest.requested_execution_level = node.attribute("level").map(str::to_string);
+                    manifest.ui_access = node.attribute("uiAccess").map(parse_bool);
+                }
+                "autoElevate" => manifest.auto_elevate = node.text().map(parse_bool),
+                "dpiAware" => manifest.dpi_aware = node.text().map(|t| t.trim().to_string()),
+                "dpiAwareness" => manifest.dpi_awareness = node.text().map(|t| t.trim().to_string()),
+                "supportedOS" => {
+                    if let Some(id) = node.attribute("Id") {
+                        manifest.supported_os.push(id.to_string());
+                    }
+                }
+                "dependentAssembly" => {
+                    let identity = node
+                        .children()
+                        .find(|c| c.is_element() && c.tag_name().name() == "assemblyIdentity");
+                    if let Some(identity) = identity {
+                        let attribute = |name| identity.attribute(name).map(str::to_string);
+                        manifest.dependent_assemblies.push(DependentAssembly {
+                            name: attribute("name").unwrap_or_default(),
+                            version: attribute("version"),
+                            assembly_type: attribute("type"),
+                            processor_architecture: attribute("processorArchitecture"),
+                            public_key_token: attribute("publicKeyToken"),
+                            language: attribute("language"),
+                        });
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        manifest.xml = xml;
+        Ok(manifest)
+    }
+}
+
+fn parse_bool(value: &str) -> bool {
+    value.trim().eq_ignore_ascii_case("true")
+}
+
+/// parses the first `RT_MANIFEST` resource
+pub(crate) fn manifest(pefile: &PEFile) -> Result<Option<AssemblyManifest>> {
+    let tree = pefile.resources_tree()?;
+    let resource = tree.iter_type(ResourceType::RT_MANIFEST).next();
+    match resource {
+        None => Ok(None),
+        Some(entry) => Ok(Some(AssemblyManifest::parse(
+            entry.name.clone(),
+            entry.language.lang_id(),
+            entry.language.data.bytes(pefile)?,
+        )?)),
+    }
+}
@@ -0,0 +1,215 @@
+use from_bytes::*;
+use crate::error::{Error, Result};
+use crate::pefile::PEFile;
+use crate::resources::{ResourceType, TemplateReader};
+use crate::winnt::*;
+
+/// maximum nesting level of popup menus
+const MAX_DEPTH: usize = 64;
+
+/// an item of a menu, which is either a command, a separator or a popup menu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MenuItem {
+    /// the command id, which is always 0 for popups of normal menus
+    pub id: u32,
+
+    /// the `MF_*` options of normal menus, or the `MFT_*` type of extended menus
+    pub flags: u32,
+
+    /// the `MFS_*` state, which is only stored in extended menus
+    pub state: u32,
+
+    /// only stored for popups of extended menus
+    pub help_id: u32,
+    pub text: String,
+    pub popup: bool,
+    pub children: Vec<MenuItem>,
+}
+
+impl MenuItem {
+    pub fn is_separator(&self) -> bool {
+        !self.popup && (self.flags & MFT_SEPARATOR != 0 || (self.id == 0 && self.text.is_empty()))
+    }
+}
+
+/// an `RT_MENU` resource, which is stored either as normal or as extended menu template
+#[derive(Debug, Clone)]
+pub struct Menu {
+    pub name: EntryIdentifier,
+    pub lang_id: Option<u16>,
+
+    /// `true` if the menu is stored as `MENUEX_TEMPLATE`
+    pub extended: bool,
+    pub help_id: u32,
+    pub items: Vec<MenuItem>,
+}
+
+impl Menu {
+    pub(crate) fn parse(name: EntryIdentifier, lang_id: Option<u16>, data: &[u8]) -> Result<Self> {
+        let mut reader = TemplateReader::new(data);
+        let version = MENUITEMTEMPLATEHEADER::from_bytes(data, 0)?.versionNumber;
+        let (extended, help_id, items) = match version {
+            0 => {
+                let header = reader.read::<MENUITEMTEMPLATEHEADER>()?;
+                reader.seek(MENUITEMTEMPLATEHEADER::packed_size() + header.offset as usize);
+                (false, 0, Self::parse_items(&mut reader, 0)?)
+            }
+            1 => {
+                let header = reader.read::<MENUEX_TEMPLATE_HEADER>()?;
+                reader.seek(4 + header.wOffset as usize);
+                (true, header.dwHelpId, Self::parse_items_ex(&mut reader, 0)?)
+            }
+            version => {
+                return Err(Error::InvalidData {
+                    offset: 0,
+                    reason: format!("unknown menu template version {}", version),
+                })
+            }
+        };
+        Ok(Self { name, lang_id, extended, help_id, items })
+    }
+
+    fn check_depth(reader: &TemplateReader<'_>, depth: usize) -> Result<()> {
+        if depth > MAX_DEPTH {
+            return Err(Error::InvalidData {
+                offset: reader.offset(),
+                reason: "popup menus are nested too deeply".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// parses the items of a normal menu, up to the item which has the `MF_END` flag
+    fn parse_items(reader: &mut TemplateReader<'_>, depth: usize) -> Result<Vec<MenuItem>> {
+        Self::check_depth(reader, depth)?;
+        let mut items = Vec::new();
+        loop {
+            let option = reader.u16()?;
+            let popup = option & MF_POPUP != 0;
+            let id = if popup { 0 } else { reader.u16()? as u32 };
+            let text = reader.string()?;
+            let children = if popup { Self::parse_items(reader, depth + 1)? } else { Vec::new() };
+            items.push(MenuItem {
+                id,
+                flags: (option & !MF_END) as u32,
+                state: 0,
+                help_id: 0,
+                text,
+                popup,
+                children,
+            });
+            if option & MF_END != 0 {
+                return Ok(items);
+            }
+        }
+    }
+
+    /// parses the items of an extended menu, up to the item which has the `MFR_END` flag.
+    /// Every item is aligned to 32 bit.
+    fn parse_items_ex(reader: &mut TemplateReader<'_>, depth: usize) -> Result<Vec<MenuItem>> {
+        Self::check_depth(reader, depth)?;
+        let mut items = Vec::new();
+        loop {
+            reader.align(4);
+            let flags = reader.u32()?;
+            let state = reader.u32()?;
+            let id = reader.u32()?;
+            let res_info = reader.u16()?;
+            let text = reader.string()?;
+            let popup = res_info & MFR_POPUP != 0;
+            let (help_id, children) = if popup {
+                reader.align(4);
+                let help_id = reader.u32()?;
+                (help_id, Self::parse_items_ex(reader, depth + 1)?)
+            } else {
+                (0, Vec::new())
+            };
+            items.push(MenuItem { id, flags, state, help_id, text, popup, children });
+            if res_info & MFR_END != 0 {
+                return Ok(items);
+            }
+        }
+    }
+}
+
+/// a keyboard shortcut of an accelerator table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Accelerator {
+    /// the `F*` flags, without the marker of the last entry
+    pub flags: u16,
+
+    /// a virtual key code if `FVIRTKEY` is set, otherwise a character
+    pub key: u16,
+
+    /// the command id
+    pub id: u16,
+}
+
+impl Accelerator {
+    pub fn is_virtual_key(&self) -> bool {
+        self.flags & FVIRTKEY != 0
+    }
+
+    pub fn no_invert(&self) -> bool {
+        self.flags & FNOINVERT != 0
+    }
+
+    pub fn shift(&self) -> bool {
+        self.flags & FSHIFT != 0
+    }
+
+    pub fn control(&self) -> bool {
+        self.flags & FCONTROL != 0
+    }
+
+    pub fn alt(&self) -> bool {
+        self.flags & FALT != 0
+    }
+}
+
+/// an `RT_ACCELERATOR` resource
+#[derive(Debug, Clone)]
+pub struct AcceleratorTable {
+    pub name: EntryIdentifier,
+    pub lang_id: Option<u16>,
+    pub accelerators: Vec<Accelerator>,
+}
+
+impl AcceleratorTable {
+    pub(crate) fn parse(name: EntryIdentifier, lang_id: Option<u16>, data: &[u8]) -> Result<Self> {
+        let mut reader = TemplateReader::new(data);
+        let mut accelerators = Vec::new();
+
+        // the last entry is marked, but some tables end without the marker
+        while !reader.is_at_end() {
+            let entry = reader.read::<ACCELTABLEENTRY>()?;
+            accelerators.push(Accelerator {
+                flags: entry.fFlags & !ACCEL_LAST,
+                key: entry.wAnsi,
+                id: entry.wId,
+            });
+            if entry.fFlags & ACCEL_LAST != 0 {
+                break;
+            }
+        }
+        Ok(Self { name, lang_id, accelerators })
+    }
+}
+
+/// parses all `RT_MENU` resources
+pub(crate) fn menus(pefile: &PEFile) -> Result<Vec<Menu>> {
+    let tree = pefile.resources_tree()?;
+    tree.iter_type(ResourceType::RT_MENU)
+        .map(|entry| Menu::parse(entry.name.clone(), entry.language.lang_id(), entry.language.data.bytes(pefile)?))
+        .collect()
+}
+
+/// parses all `RT_ACCELERATOR` resources
+pub(crate) fn accelerator_tables(pefile: &PEFile) -> Result<Vec<AcceleratorTable>> {
+    let tree = pefile.resources_tree()?;
+    tree.iter_type(ResourceType::RT_ACCELERATOR)
+        .map(|entry| {
+            AcceleratorTable::parse(entry.name.clone(), entry.language.lang_id(), entry.language.data.bytes(pefile)?)
+        })
+        .collect()
+}
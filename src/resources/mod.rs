@@ -0,0 +1,260 @@
+mod bitmap;
+mod dialog;
+mod font;
+mod icons;
+mod manifest;
+mod menu;
+mod mui;
+mod reader;
+mod strings;
+mod version;
+mod visitor;
+pub use bitmap::*;
+pub use dialog::*;
+pub use font::*;
+pub use icons::*;
+pub use manifest::*;
+pub use menu::*;
+pub use mui::*;
+pub use reader::*;
+pub use strings::*;
+pub use version::*;
+pub use visitor::*;
+
+use crate::pefile::PEFile;
+use crate::winnt::*;
+use crate::error::Result;
+
+/// predefined resource types
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceType {
+    RT_CURSOR = 1,
+    RT_BITMAP = 2,
+    RT_ICON = 3,
+    RT_MENU = 4,
+    RT_DIALOG = 5,
+    RT_STRING = 6,
+    RT_FONTDIR = 7,
+    RT_FONT = 8,
+    RT_ACCELERATOR = 9,
+    RT_RCDATA = 10,
+    RT_MESSAGETABLE = 11,
+    RT_GROUP_CURSOR = 12,
+    RT_GROUP_ICON = 14,
+    RT_VERSION = 16,
+    RT_MANIFEST = 24,
+}
+
+impl From<ResourceType> for EntryIdentifier {
+    fn from(resource_type: ResourceType) -> Self {
+        EntryIdentifier::Id(resource_type as u16)
+    }
+}
+
+impl From<u16> for EntryIdentifier {
+    fn from(id: u16) -> Self {
+        EntryIdentifier::Id(id)
+    }
+}
+
+impl From<&str> for EntryIdentifier {
+    fn from(name: &str) -> Self {
+        EntryIdentifier::Name(name.to_string())
+    }
+}
+
+/// the location of the data of a single resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceData {
+    pub rva: u32,
+    pub size: u32,
+    pub code_page: u32,
+}
+
+impl ResourceData {
+    /// returns the contents of the resource
+    pub fn bytes<'pefile>(&self, pefile: &'pefile PEFile) -> Result<&'pefile [u8]> {
+        pefile.bytes_at_rva(self.rva as usize, self.size as usize)
+    }
+}
+
+/// a resource in a specific language
+#[derive(Debug, Clone)]
+pub struct ResourceLanguageNode {
+    pub identifier: EntryIdentifier,
+    pub data: ResourceData,
+}
+
+impl ResourceLanguageNode {
+    /// returns the language id, unless the language is identified by a name
+    pub fn lang_id(&self) -> Option<u16> {
+        match self.identifier {
+            EntryIdentifier::Id(id) => Some(id),
+            _ => None,
+        }
+    }
+}
+
+/// a resource, which may be available in several languages
+#[derive(Debug, Clone)]
+pub struct ResourceNameNode {
+    pub identifier: EntryIdentifier,
+    pub languages: Vec<ResourceLanguageNode>,
+}
+
+/// all resources of a specific type
+#[derive(Debug, Clone)]
+pub struct ResourceTypeNode {
+    pub identifier: EntryIdentifier,
+    pub names: Vec<ResourceNameNode>,
+}
+
+/// a resource which has been found in a [ResourceTree]
+#[derive(Debug, Clone, Copy)]
+pub struct ResourceEntry<'tree> {
+    pub resource_type: &'tree EntryIdentifier,
+    pub name: &'tree EntryIdentifier,
+    pub language: &'tree ResourceLanguageNode,
+}
+
+/// the resources of an image, organized by type, name and language
+#[derive(Debug, Clone, Default)]
+pub struct ResourceTree {
+    pub types: Vec<ResourceTypeNode>,
+}
+
+impl ResourceTree {
+    /// returns all resources of the given type
+    pub fn get_type<I: Into<EntryIdentifier>>(&self, resource_type: I) -> Option<&ResourceTypeNode> {
+        let resource_type = resource_type.into();
+        self.types.iter().find(|t| t.identifier == resource_type)
+    }
+
+    /// returns the resource with the given type and name, in all languages
+    pub fn get<T: Into<EntryIdentifier>, N: Into<EntryIdentifier>>(
+        &self,
+        resource_type: T,
+        name: N,
+    ) -> Option<&ResourceNameNode> {
+        let name = name.into();
+        self.get_type(resource_type)?.names.iter().find(|n| n.identifier == name)
+    }
+
+    /// returns the data of a resource. If `lang_id` is `None` or the resource is not
+    /// available in this language, the first language is used.
+    pub fn find<T: Into<EntryIdentifier>, N: Into<EntryIdentifier>>(
+        &self,
+        resource_type: T,
+        name: N,
+        lang_id: Option<u16>,
+    ) -> Option<&ResourceData> {
+        let languages = &self.get(resource_type, name)?.languages;
+        lang_id
+            .and_then(|lang_id| languages.iter().find(|l| l.lang_id() == Some(lang_id)))
+            .or_else(|| languages.first())
+            .map(|l| &l.data)
+    }
+
+    /// iterates over all resources of all types
+    pub fn iter(&self) -> impl Iterator<Item = ResourceEntry<'_>> {
+        self.types.iter().flat_map(|t| {
+            t.names.iter().flat_map(move |n| {
+                n.languages.iter().map(move |l| ResourceEntry {
+                    resource_type: &t.identifier,
+                    name: &n.identifier,
+                    language: l,
+                })
+            })
+        })
+    }
+
+    /// iterates over all resources of the given type, in all languages
+    pub fn iter_type<I: Into<EntryIdentifier>>(&self, resource_type: I) -> impl Iterator<Item = ResourceEntry<'_>> {
+        let resource_type = resource_type.into();
+        self.iter().filter(move |e| *e.resource_type == resource_type)
+    }
+}
+
+/// creates a [ResourceTree]
+#[derive(Default)]
+pub(crate) struct ResourceTreeVisitor {
+    id_stack: Vec<EntryIdentifier>,
+    tree: ResourceTree,
+}
+
+impl ResourceTreeVisitor {
+    pub fn into_tree(self) -> ResourceTree {
+        self.tree
+    }
+}
+
+impl ResourceDirectoryVisitor for ResourceTreeVisitor {
+    fn enter_resource_directory(
+        &mut self,
+        _dir: &IMAGE_RESOURCE_DIRECTORY,
+        identifier: &EntryIdentifier,
+    ) -> Result<()> {
+        match identifier {
+            EntryIdentifier::NoIdentifier => (),
+            _ => self.id_stack.push(identifier.clone()),
+        }
+        Ok(())
+    }
+
+    fn leave_resource_directory(
+        &mut self,
+        _dir: &IMAGE_RESOURCE_DIRECTORY,
+        identifier: &EntryIdentifier,
+    ) -> Result<()> {
+        match identifier {
+            EntryIdentifier::NoIdentifier => (),
+            _ => { let _ = self.id_stack.pop(); }
+        }
+        Ok(())
+    }
+
+    fn visit_resource_data_entry(
+        &mut self,
+        entry: &IMAGE_RESOURCE_DATA_ENTRY,
+        identifier: &EntryIdentifier,
+    ) -> Result<()> {
+        let (resource_type, name) = match &self.id_stack[..] {
+            [resource_type, name] => (resource_type, name),
+            _ => {
+                log::warn!(
+                    "ignoring resource {} at unexpected depth {}",
+                    identifier,
+                    self.id_stack.len()
+                );
+                return Ok(());
+            }
+        };
+
+        let types = &mut self.tree.types;
+        let type_idx = match types.iter().position(|t| t.identifier == *resource_type) {
+            Some(idx) => idx,
+            None => {
+                types.push(ResourceTypeNode { identifier: resource_type.clone(), names: Vec::new() });
+                types.len() - 1
+            }
+        };
+        let names = &mut types[type_idx].names;
+        let name_idx = match names.iter().position(|n| n.identifier == *name) {
+            Some(idx) => idx,
+            None => {
+                names.push(ResourceNameNode { identifier: name.clone(), languages: Vec::new() });
+                names.len() - 1
+            }
+        };
+        names[name_idx].languages.push(ResourceLanguageNode {
+            identifier: identifier.clone(),
+            data: ResourceData {
+                rva: entry.OffsetToData,
+                size: entry.Size,
+                code_page: entry.CodePage,
+            },
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+use byteorder::{ByteOrder, LittleEndian};
+use std::path::{Path, PathBuf};
+use crate::error::{Error, Result};
+use crate::msg::Message;
+use crate::pefile::PEFile;
+use crate::utils::bytes_at;
+
+pub const MUI_SIGNATURE: u32 = 0xfecdfecd;
+
+/// a language neutral file, which contains the code and the resources which are not localized
+pub const MUI_FILETYPE_LANGUAGE_NEUTRAL_MAIN: u32 = 0x11;
+
+/// a `.mui` file, which contains the localized resources of a language neutral file
+pub const MUI_FILETYPE_LANGUAGE_SPECIFIC_MUI: u32 = 0x12;
+
+/// the resources of the ultimate fallback language are stored in the language neutral file
+pub const MUI_ULTIMATE_FALLBACK_LOCATION_INTERNAL: u32 = 1;
+
+const MUI_HEADER_SIZE: usize = 0x84;
+
+/// the contents of the `MUI` resource, which links a language neutral file
+/// with its language specific `.mui` files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MuiConfig {
+    pub version: u32,
+    pub file_type: u32,
+    pub system_attributes: u32,
+
+    /// where the resources of the fallback language are stored, see [`MuiConfig::has_internal_fallback`]
+    pub ultimate_fallback_location: u32,
+    pub service_checksum: [u8; 16],
+    pub checksum: [u8; 16],
+
+    /// the resource types which are stored in the language neutral file
+    pub main_type_names: Vec<String>,
+    pub main_type_ids: Vec<u32>,
+
+    /// the resource types which are stored in the `.mui` files
+    pub mui_type_names: Vec<String>,
+    pub mui_type_ids: Vec<u32>,
+
+    /// the language of a `.mui` file
+    pub language: Option<String>,
+
+    /// the language which is used if no `.mui` file of the requested languages exists
+    pub fallback_language: Option<String>,
+}
+
+impl MuiConfig {
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let header = bytes_at(data, 0, MUI_HEADER_SIZE)?;
+        let u32_at = |offset: usize| LittleEndian::read_u32(&header[offset..offset + 4]);
+        let checksum_at = |offset: usize| {
+            let mut checksum = [0; 16];
+            checksum.copy_from_slice(&header[offset..offset + 16]);
+            checksum
+        };
+
+        if u32_at(0) != MUI_SIGNATURE {
+            return Err(Error::InvalidData {
+                offset: 0,
+                reason: format!("invalid MUI signature 0x{:08x}", u32_at(0)),
+            });
+        }
+
+        // the variable length fields are referenced by offset and size, both relative to the resource
+        let field = |offset: usize| -> Result<&[u8]> {
+            match (u32_at(offset) as usize, u32_at(offset + 4) as usize) {
+                (0, _) | (_, 0) => Ok(&[]),
+                (start, size) => bytes_at(data, start, size),
+            }
+        };
+        let strings = |offset: usize| -> Result<Vec<String>> {
+            let chars: Vec<u16> = field(offset)?.chunks_exact(2).map(LittleEndian::read_u16).collect();
+            Ok(chars
+                .split(|&c| c == 0)
+                .filter(|s| !s.is_empty())
+                .map(String::from_utf16_lossy)
+                .collect())
+        };
+        let ids = |offset: usize| -> Result<Vec<u32>> {
+            Ok(field(offset)?.chunks_exact(4).map(LittleEndian::read_u32).collect())
+        };
+
+        Ok(Self {
+            version: u32_at(0x08),
+            file_type: u32_at(0x10),
+            system_attributes: u32_at(0x14),
+            ultimate_fallback_location: u32_at(0x18),
+            service_checksum: checksum_at(0x1c),
+            checksum: checksum_at(0x2c),
+            main_type_names: strings(0x54)?,
+            main_type_ids: ids(0x5c)?,
+            mui_type_names: strings(0x64)?,
+            mui_type_ids: ids(0x6c)?,
+            language: strings(0x74)?.into_iter().next(),
+            fallback_language: strings(0x7c)?.into_iter().next(),
+        })
+    }
+
+    pub fn is_language_neutral(&self) -> bool {
+        self.file_type == MUI_FILETYPE_LANGUAGE_NEUTRAL_MAIN
+    }
+
+    pub fn is_mui(&self) -> bool {
+        self.file_type == MUI_FILETYPE_LANGUAGE_SPECIFIC_MUI
+    }
+
+    /// returns `true` if the resources of the fallback language are stored in the
+    /// language neutral file instead of a `.mui` file
+    pub fn has_internal_fallback(&self) -> bool {
+        self.ultimate_fallback_location == MUI_ULTIMATE_FALLBACK_LOCATION_INTERNAL
+    }
+
+    /// returns `true` if the `.mui` file which has the configuration `mui` belongs to the
+    /// language neutral file which has this configuration. Either the checksums or the
+    /// service checksums must be equal; checksums which are not set are never equal.
+    pub fn matches(&self, mui: &MuiConfig) -> bool {
+        let is_set = |checksum: &[u8; 16]| checksum.iter().any(|&b| b != 0);
+        (is_set(&self.checksum) && self.checksum == mui.checksum)
+            || (is_set(&self.service_checksum) && self.service_checksum == mui.service_checksum)
+    }
+}
+
+/// finds the `.mui` file which contains the localized resources of a language neutral file
+pub struct MuiResolver {
+    base: PEFile,
+    mui: Option<PEFile>,
+}
+
+impl MuiResolver {
+    /// searches `<root>/<language>/<file name>.mui` for all of the given `languages`, and for the
+    /// ultimate fallback language of `base`. Files which cannot be parsed or whose checksum
+    /// does not match `base` are ignored.
+    ///
+    /// If `base` has no `MUI` resource or if no matching `.mui` file can be found,
+    /// the resources are taken from `base`. This is also the case if the ultimate fallback
+    /// location of `base` says that the fallback language is stored in `base` itself.
+    ///
+    /// # Errors
+    /// Returns [`Error::MissingFileName`] if `base` has a `MUI` resource but has not been
+    /// loaded with [`PEFile::new`], because the name of the `.mui` file is derived from
+    /// the file name of `base`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use libpefile::*;
+    /// # use std::path::PathBuf;
+    /// # fn main() -> Result<()> {
+    /// let base = PEFile::new(PathBuf::from("C:\\Windows\\System32\\msaudite.dll"))?;
+    /// let resolver = MuiResolver::new(base, "C:\\Windows\\System32", &["de-DE"])?;
+    /// for msg in resolver.messages_iter()?.filter_map(|r| r.ok()) {
+    ///     println!("{}: '{}'", msg.msg_id, msg.text);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new<P: AsRef<Path>>(base: PEFile, root: P, languages: &[&str]) -> Result<Self> {
+        let config = match base.mui_config()? {
+            None => return Ok(Self { base, mui: None }),
+            Some(config) => config,
+        };
+        let file_name = match base.filename().and_then(Path::file_name) {
+            Some(file_name) => format!("{}.mui", file_name.to_string_lossy()),
+            None => return Err(Error::MissingFileName),
+        };
+
+        let mut candidates: Vec<PathBuf> = languages
+            .iter()
+            .map(|language| root.as_ref().join(language).join(&file_name))
+            .collect();
+        if let Some(language) = config.fallback_language.as_ref().filter(|_| !config.has_internal_fallback()) {
+            candidates.push(root.as_ref().join(language).join(&file_name));
+        }
+
+        for candidate in candidates {
+            if !candidate.is_file() {
+                continue;
+            }
+            // a broken file for one language must not hide the files of the other languages
+            let mui = match PEFile::new(candidate.clone()) {
+                Ok(mui) => mui,
+                Err(why) => {
+                    log::warn!("ignoring {}: {}", candidate.display(), why);
+                    continue;
+                }
+            };
+            match mui.mui_config() {
+                Ok(Some(mui_config)) if mui_config.is_mui() && config.matches(&mui_config) => {
+                    log::debug!("using {}", candidate.display());
+                    return Ok(Self { base, mui: Some(mui) });
+                }
+                Ok(Some(_)) => log::warn!("the checksum of {} does not match", candidate.display()),
+                Ok(None) => log::warn!("{} has no MUI resource", candidate.display()),
+                Err(why) => log::warn!("ignoring {}: {}", candidate.display(), why),
+            }
+        }
+        Ok(Self { base, mui: None })
+    }
+
+    /// returns the language neutral file
+    pub fn base(&self) -> &PEFile {
+        &self.base
+    }
+
+    /// returns the `.mui` file, if one has been found
+    pub fn mui(&self) -> Option<&PEFile> {
+        self.mui.as_ref()
+    }
+
+    /// returns the file which contains the localized resources
+    pub fn resources(&self) -> &PEFile {
+        self.mui.as_ref().unwrap_or(&self.base)
+    }
+
+    /// returns an iterator over all messages of the `.mui` file, or of the
+    /// language neutral file if there is no `.mui` file
+    pub fn messages_iter(&self) -> Result<impl Iterator<Item = Result<Message>> + '_> {
+        self.resources().messages_iter()
+    }
+}
@@ -0,0 +1,112 @@
+use byteorder::{ByteOrder, LittleEndian};
+use from_bytes::*;
+use crate::error::{Error, Result};
+use crate::utils::bytes_at;
+
+/// a field of a resource template, which is either a string or an ordinal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameOrOrdinal {
+    /// the field is empty
+    None,
+    Ordinal(u16),
+    Name(String),
+}
+
+impl std::fmt::Display for NameOrOrdinal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NameOrOrdinal::None => Ok(()),
+            NameOrOrdinal::Ordinal(ordinal) => write!(f, "#{}", ordinal),
+            NameOrOrdinal::Name(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// reads the variable length structures of dialog and menu templates
+pub(crate) struct TemplateReader<'data> {
+    data: &'data [u8],
+    offset: usize,
+}
+
+impl<'data> TemplateReader<'data> {
+    pub fn new(data: &'data [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.offset >= self.data.len()
+    }
+
+    /// continues reading at `offset`, relative to the start of the template
+    pub fn seek(&mut self, offset: usize) {
+        self.offset = offset;
+    }
+
+    pub fn bytes(&mut self, length: usize) -> Result<&'data [u8]> {
+        let bytes = bytes_at(self.data, self.offset, length)?;
+        self.offset += length;
+        Ok(bytes)
+    }
+
+    pub fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16> {
+        Ok(LittleEndian::read_u16(self.bytes(2)?))
+    }
+
+    pub fn u32(&mut self) -> Result<u32> {
+        Ok(LittleEndian::read_u32(self.bytes(4)?))
+    }
+
+    pub fn read<T: StructFromBytes + PackedSize>(&mut self) -> Result<Box<T>> {
+        let value = T::from_bytes(self.data, self.offset)?;
+        self.offset += T::packed_size();
+        Ok(value)
+    }
+
+    /// skips the padding up to the next multiple of `alignment`, relative to the start of the template
+    pub fn align(&mut self, alignment: usize) {
+        self.offset = self.offset.next_multiple_of(alignment);
+    }
+
+    /// reads a NUL-terminated UTF-16 string
+    pub fn string(&mut self) -> Result<String> {
+        let start = self.offset;
+        let mut chars = Vec::new();
+        loop {
+            match self.u16() {
+                Ok(0) => break,
+                Ok(c) => chars.push(c),
+                Err(_) => {
+                    return Err(Error::InvalidData {
+                        offset: start,
+                        reason: "string is not terminated".to_string(),
+                    })
+                }
+            }
+        }
+        Ok(String::from_utf16_lossy(&chars))
+    }
+
+    /// reads a field which is empty (`0x0000`), an ordinal (`0xffff` followed by the ordinal)
+    /// or a NUL-terminated UTF-16 string
+    pub fn name_or_ordinal(&mut self) -> Result<NameOrOrdinal> {
+        match LittleEndian::read_u16(bytes_at(self.data, self.offset, 2)?) {
+            0x0000 => {
+                self.offset += 2;
+                Ok(NameOrOrdinal::None)
+            }
+            0xffff => {
+                self.offset += 2;
+                Ok(NameOrOrdinal::Ordinal(self.u16()?))
+            }
+            _ => Ok(NameOrOrdinal::Name(self.string()?)),
+        }
+    }
+}
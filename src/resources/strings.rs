@@ -0,0 +1,143 @@
+use byteorder::{ByteOrder, LittleEndian};
+use crate::error::{Error, Result};
+use crate::msg::LangId;
+use crate::pefile::PEFile;
+use crate::resources::{ResourceData, ResourceEntry, ResourceTree, ResourceType};
+use crate::utils::{bytes_at, utf16_from_slice};
+use crate::winnt::EntryIdentifier;
+
+/// number of strings in every `RT_STRING` resource
+pub const STRINGS_PER_BUNDLE: u16 = 16;
+
+/// a string from an `RT_STRING` resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceString {
+    pub id: u16,
+    pub lang_id: u32,
+    pub text: String,
+}
+
+impl ResourceString {
+    /// returns the language of this string
+    pub fn language(&self) -> LangId {
+        LangId::from(self.lang_id)
+    }
+}
+
+/// returns the name of the `RT_STRING` resource which contains the string `id`
+fn bundle_of(id: u16) -> u16 {
+    id / STRINGS_PER_BUNDLE + 1
+}
+
+/// a single `RT_STRING` resource, which contains up to 16 strings
+struct StringBundle {
+    bundle_id: u16,
+    lang_id: u16,
+    data: ResourceData,
+}
+
+impl StringBundle {
+    fn from_entry(entry: &ResourceEntry<'_>) -> Result<Self> {
+        let bundle_id = match entry.name {
+            EntryIdentifier::Id(id) if *id > 0 => *id,
+            id => return Err(Error::InvalidResourceLayout(format!(
+                "string table is identified by {}, not by a bundle id", id))),
+        };
+        let lang_id = match entry.language.lang_id() {
+            Some(lang_id) => lang_id,
+            None => return Err(Error::InvalidResourceLayout(format!(
+                "string table language is identified by {}, not by an id",
+                entry.language.identifier))),
+        };
+        Ok(Self { bundle_id, lang_id, data: entry.language.data })
+    }
+
+    /// decodes all strings which are not empty. Every string consists of its length in characters,
+    /// followed by the UTF-16 encoded characters without a terminating NUL.
+    fn strings(&self, pefile: &PEFile) -> Result<Vec<ResourceString>> {
+        let data = self.data.bytes(pefile)?;
+        let first_id = (self.bundle_id - 1) as u32 * STRINGS_PER_BUNDLE as u32;
+        let mut strings = Vec::new();
+        let mut offset = 0;
+        for index in 0..STRINGS_PER_BUNDLE as u32 {
+            let length = LittleEndian::read_u16(bytes_at(data, offset, 2)?) as usize;
+            offset += 2;
+            if length == 0 {
+                continue;
+            }
+            let text = utf16_from_slice(data, offset, length)?;
+            offset += length * 2;
+
+            let id = first_id + index;
+            if id > u16::MAX as u32 {
+                return Err(Error::InvalidResourceLayout(format!(
+                    "string table bundle {} exceeds the range of string ids", self.bundle_id)));
+            }
+            strings.push(ResourceString {
+                id: id as u16,
+                lang_id: self.lang_id as u32,
+                text,
+            });
+        }
+        Ok(strings)
+    }
+}
+
+/// iterates over the strings of all `RT_STRING` resources
+pub struct StringTableIterator<'pefile> {
+    pefile: &'pefile PEFile,
+    bundles: std::vec::IntoIter<Result<StringBundle>>,
+    strings: std::vec::IntoIter<ResourceString>,
+}
+
+impl<'pefile> StringTableIterator<'pefile> {
+    pub(crate) fn new(pefile: &'pefile PEFile, entries: Vec<ResourceEntry<'_>>) -> Self {
+        let bundles: Vec<_> = entries.iter().map(StringBundle::from_entry).collect();
+        Self {
+            pefile,
+            bundles: bundles.into_iter(),
+            strings: Vec::new().into_iter(),
+        }
+    }
+
+    pub fn do_next(&mut self) -> Result<Option<ResourceString>> {
+        loop {
+            if let Some(string) = self.strings.next() {
+                return Ok(Some(string));
+            }
+            match self.bundles.next() {
+                None => return Ok(None),
+                Some(bundle) => self.strings = bundle?.strings(self.pefile)?.into_iter(),
+            }
+        }
+    }
+}
+
+impl<'pefile> Iterator for StringTableIterator<'pefile> {
+    type Item = Result<ResourceString>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.do_next();
+
+        // stop after the first error, like the messages iterator does
+        if result.is_err() {
+            self.bundles = Vec::new().into_iter();
+            self.strings = Vec::new().into_iter();
+        }
+        result.transpose()
+    }
+}
+
+/// searches the string `id` in the `RT_STRING` resource of the language `lang_id`
+pub(crate) fn find_string(pefile: &PEFile, tree: &ResourceTree, id: u16, lang_id: u32) -> Result<Option<ResourceString>> {
+    let bundle_id = EntryIdentifier::Id(bundle_of(id));
+    let entry = tree
+        .iter_type(ResourceType::RT_STRING)
+        .find(|e| *e.name == bundle_id && e.language.lang_id().map(u32::from) == Some(lang_id));
+    match entry {
+        None => Ok(None),
+        Some(entry) => {
+            let strings = StringBundle::from_entry(&entry)?.strings(pefile)?;
+            Ok(strings.into_iter().find(|s| s.id == id))
+        }
+    }
+}
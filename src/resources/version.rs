@@ -0,0 +1,219 @@
+use byteorder::{ByteOrder, LittleEndian};
+use from_bytes::*;
+use crate::error::{Error, Result};
+use crate::utils::bytes_at;
+use crate::winnt::*;
+
+/// maximum nesting level of blocks. The tree of a valid version info has at most four levels,
+/// e.g. `VS_VERSION_INFO`, `StringFileInfo`, the string table and the string.
+const MAX_DEPTH: usize = 16;
+
+/// a `String` of a `StringTable`, e.g. `CompanyName`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionString {
+    pub key: String,
+    pub value: String,
+}
+
+/// the strings of `StringFileInfo` for a single language and code page
+#[derive(Debug, Clone)]
+pub struct VersionStringTable {
+    /// the key of the table, which consists of the language and the code page as hexadecimal digits,
+    /// e.g. `040904b0`
+    pub key: String,
+    pub lang_id: u16,
+    pub code_page: u16,
+    pub strings: Vec<VersionString>,
+}
+
+impl VersionStringTable {
+    /// returns the value of the string named `key`
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.strings.iter().find(|s| s.key == key).map(|s| &s.value[..])
+    }
+}
+
+/// the contents of an `RT_VERSION` resource
+#[derive(Debug, Clone)]
+pub struct VersionInfo {
+    pub fixed: Option<VS_FIXEDFILEINFO>,
+    pub string_tables: Vec<VersionStringTable>,
+
+    /// the pairs of language and code page from `VarFileInfo\Translation`
+    pub translations: Vec<(u16, u16)>,
+}
+
+impl VersionInfo {
+    /// parses a `VS_VERSIONINFO` structure
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let root = Block::parse(data, 0, 0)?;
+        if root.key != "VS_VERSION_INFO" {
+            return Err(Error::InvalidData {
+                offset: 0,
+                reason: format!("unexpected version info key '{}'", root.key),
+            });
+        }
+
+        let fixed = if root.value.is_empty() {
+            None
+        } else {
+            let fixed = VS_FIXEDFILEINFO::from_bytes(root.value, 0)?;
+            if fixed.dwSignature != VS_FFI_SIGNATURE {
+                return Err(Error::InvalidData {
+                    offset: root.value_offset,
+                    reason: format!("invalid VS_FIXEDFILEINFO signature 0x{:08x}", fixed.dwSignature),
+                });
+            }
+            Some(*fixed)
+        };
+
+        let mut string_tables = Vec::new();
+        let mut translations = Vec::new();
+        for child in root.children {
+            match &child.key[..] {
+                "StringFileInfo" => {
+                    for table in child.children {
+                        string_tables.push(VersionStringTable::from_block(table)?);
+                    }
+                }
+                "VarFileInfo" => {
+                    for var in child.children.iter().filter(|v| v.key == "Translation") {
+                        translations.extend(var.value.chunks_exact(4).map(|t| {
+                            (LittleEndian::read_u16(&t[0..2]), LittleEndian::read_u16(&t[2..4]))
+                        }));
+                    }
+                }
+                key => log::warn!("ignoring unknown version info block '{}'", key),
+            }
+        }
+
+        Ok(Self { fixed, string_tables, translations })
+    }
+
+    /// returns the value of the string named `key` from the first string table which contains it
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.string_tables.iter().find_map(|t| t.get(key))
+    }
+
+    /// returns the string table for the given language and code page
+    pub fn string_table(&self, lang_id: u16, code_page: u16) -> Option<&VersionStringTable> {
+        self.string_tables
+            .iter()
+            .find(|t| t.lang_id == lang_id && t.code_page == code_page)
+    }
+
+    pub fn file_version(&self) -> Option<&str> {
+        self.get("FileVersion")
+    }
+
+    pub fn product_version(&self) -> Option<&str> {
+        self.get("ProductVersion")
+    }
+
+    pub fn company_name(&self) -> Option<&str> {
+        self.get("CompanyName")
+    }
+
+    pub fn original_filename(&self) -> Option<&str> {
+        self.get("OriginalFilename")
+    }
+}
+
+impl VersionStringTable {
+    fn from_block(block: Block<'_>) -> Result<Self> {
+        let (lang_id, code_page) = match u32::from_str_radix(&block.key, 16) {
+            Ok(id) if block.key.len() == 8 => ((id >> 16) as u16, id as u16),
+            _ => {
+                return Err(Error::InvalidData {
+                    offset: block.value_offset,
+                    reason: format!("invalid string table key '{}'", block.key),
+                })
+            }
+        };
+        let strings = block
+            .children
+            .into_iter()
+            .map(|s| VersionString {
+                value: utf16_until_nul(s.value),
+                key: s.key,
+            })
+            .collect();
+        Ok(Self { key: block.key, lang_id, code_page, strings })
+    }
+}
+
+/// a node of the `VS_VERSIONINFO` tree. Every node consists of a header, a key, an optional value
+/// and its children; all of them are aligned to 32 bit
+struct Block<'data> {
+    key: String,
+    value: &'data [u8],
+    value_offset: usize,
+    children: Vec<Block<'data>>,
+}
+
+const BLOCK_HEADER_SIZE: usize = 6;
+
+impl<'data> Block<'data> {
+    fn parse(data: &'data [u8], offset: usize, depth: usize) -> Result<Self> {
+        if depth > MAX_DEPTH {
+            return Err(Error::InvalidData {
+                offset,
+                reason: "version info blocks are nested too deeply".to_string(),
+            });
+        }
+        let header = bytes_at(data, offset, BLOCK_HEADER_SIZE)?;
+        let length = LittleEndian::read_u16(&header[0..2]) as usize;
+        let value_length = LittleEndian::read_u16(&header[2..4]) as usize;
+        let is_text = LittleEndian::read_u16(&header[4..6]) == 1;
+        if length < BLOCK_HEADER_SIZE {
+            return Err(Error::InvalidData {
+                offset,
+                reason: format!("version info block is too small: {} bytes", length),
+            });
+        }
+        let block = bytes_at(data, offset, length)?;
+
+        let mut key = Vec::new();
+        let mut pos = BLOCK_HEADER_SIZE;
+        loop {
+            let c = LittleEndian::read_u16(bytes_at(block, pos, 2)?);
+            pos += 2;
+            if c == 0 {
+                break;
+            }
+            key.push(c);
+        }
+        let key = String::from_utf16_lossy(&key);
+        pos = pos.next_multiple_of(4);
+
+        // the length of text values is given in characters, but some compilers store bytes
+        let value_size = if is_text { value_length * 2 } else { value_length };
+        let value_start = pos.min(block.len());
+        let value_end = (pos + value_size).min(block.len());
+        let value = &block[value_start..value_end];
+        pos = value_end.next_multiple_of(4);
+
+        let mut children = Vec::new();
+        while pos + BLOCK_HEADER_SIZE <= block.len() {
+            // some linkers add zero padding after the last child
+            let child_length = LittleEndian::read_u16(&block[pos..pos + 2]) as usize;
+            if child_length == 0 {
+                break;
+            }
+            children.push(Block::parse(block, pos, depth + 1)?);
+            pos = (pos + child_length).next_multiple_of(4);
+        }
+
+        Ok(Self { key, value, value_offset: offset + value_start, children })
+    }
+}
+
+/// decodes an UTF-16 string, which ends at the first NUL character or at the end of `data`
+fn utf16_until_nul(data: &[u8]) -> String {
+    let chars: Vec<u16> = data
+        .chunks_exact(2)
+        .map(LittleEndian::read_u16)
+        .take_while(|&c| c != 0)
+        .collect();
+    String::from_utf16_lossy(&chars)
+}
@@ -0,0 +1,30 @@
+use crate::winnt::*;
+use crate::error::Result;
+
+/// receives the directories and data entries of the resource tree,
+/// while it is being walked by [PEFile::visit_resource_tree](crate::PEFile::visit_resource_tree).
+///
+/// The root directory is identified by [EntryIdentifier::NoIdentifier]. Usually, the
+/// directories at the next levels are identified by the resource type and the resource name,
+/// and the data entries are identified by their language.
+pub trait ResourceDirectoryVisitor {
+    fn init(&mut self) {}
+    fn finalize(&mut self) {}
+
+    fn enter_resource_directory(
+        &mut self,
+        dir: &IMAGE_RESOURCE_DIRECTORY,
+        identifier: &EntryIdentifier,
+    ) -> Result<()>;
+    fn leave_resource_directory(
+        &mut self,
+        dir: &IMAGE_RESOURCE_DIRECTORY,
+        identifier: &EntryIdentifier,
+    ) -> Result<()>;
+
+    fn visit_resource_data_entry(
+        &mut self,
+        entry: &IMAGE_RESOURCE_DATA_ENTRY,
+        identifier: &EntryIdentifier,
+    ) -> Result<()>;
+}
@@ -1,8 +1,27 @@
-pub fn utf16_from_slice(slice: &[u8], mut offset: usize, characters: usize) -> String {
-    let mut name_chars = Vec::new();
-    for _ in 0..characters {
-        name_chars.push(slice[offset] as u16 | ((slice[offset+1] as u16)<<8 as u8));
-        offset += 2;
+use crate::error::{Error, Result};
+
+/// returns `length` bytes of `slice`, starting at `offset`, or fails if `slice` is too short
+pub fn bytes_at(slice: &[u8], offset: usize, length: usize) -> Result<&[u8]> {
+    offset
+        .checked_add(length)
+        .and_then(|end| slice.get(offset..end))
+        .ok_or(Error::Truncated { offset, needed: length })
+}
+
+/// reads a NUL-terminated ASCII string, starting at `offset`
+pub fn cstring_from_slice(slice: &[u8], offset: usize) -> Result<String> {
+    let bytes = slice.get(offset..).ok_or(Error::Truncated { offset, needed: 1 })?;
+    match bytes.iter().position(|&c| c == 0) {
+        Some(len) => Ok(String::from_utf8_lossy(&bytes[..len]).to_string()),
+        None => Err(Error::Truncated { offset, needed: bytes.len() + 1 }),
     }
-    String::from_utf16_lossy(&name_chars[..])
+}
+
+pub fn utf16_from_slice(slice: &[u8], offset: usize, characters: usize) -> Result<String> {
+    let bytes = bytes_at(slice, offset, characters * 2)?;
+    let name_chars: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| c[0] as u16 | ((c[1] as u16)<<8))
+        .collect();
+    Ok(String::from_utf16_lossy(&name_chars[..]))
 }
@@ -0,0 +1,321 @@
+use byteorder::{ByteOrder, LittleEndian};
+use crate::error::{Error, Result};
+use crate::utils::{bytes_at, utf16_from_slice};
+
+const TOKEN_EOF: u8 = 0x00;
+const TOKEN_OPEN_START_ELEMENT: u8 = 0x01;
+const TOKEN_CLOSE_START_ELEMENT: u8 = 0x02;
+const TOKEN_CLOSE_EMPTY_ELEMENT: u8 = 0x03;
+const TOKEN_END_ELEMENT: u8 = 0x04;
+const TOKEN_VALUE: u8 = 0x05;
+const TOKEN_ATTRIBUTE: u8 = 0x06;
+const TOKEN_CDATA_SECTION: u8 = 0x07;
+const TOKEN_CHAR_REF: u8 = 0x08;
+const TOKEN_ENTITY_REF: u8 = 0x09;
+const TOKEN_PI_TARGET: u8 = 0x0a;
+const TOKEN_PI_DATA: u8 = 0x0b;
+const TOKEN_NORMAL_SUBSTITUTION: u8 = 0x0d;
+const TOKEN_OPTIONAL_SUBSTITUTION: u8 = 0x0e;
+const TOKEN_FRAGMENT_HEADER: u8 = 0x0f;
+
+/// set in the token type if more data (e.g. attributes) follows
+const TOKEN_HAS_MORE_DATA: u8 = 0x40;
+
+/// value type of strings in `TOKEN_VALUE`
+const VALUE_TYPE_STRING: u8 = 0x01;
+
+/// limits the nesting of elements, to protect against stack exhaustion
+const MAX_DEPTH: usize = 64;
+
+/// a part of a text or attribute value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XmlValue {
+    Text(String),
+
+    /// a reference to a character, such as `&#x0a;`
+    CharRef(u16),
+
+    /// a reference to an entity, such as `&amp;`
+    EntityRef(String),
+
+    /// a placeholder for the value with the given index, which is provided by the event record.
+    /// Optional substitutions are omitted if the value is empty.
+    Substitution { index: u16, value_type: u8, optional: bool },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlAttribute {
+    pub name: String,
+    pub value: Vec<XmlValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlElement {
+    pub name: String,
+    pub attributes: Vec<XmlAttribute>,
+    pub children: Vec<XmlNode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XmlNode {
+    Element(XmlElement),
+    Value(XmlValue),
+    CData(String),
+    ProcessingInstruction { target: String, data: String },
+}
+
+fn escape(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+}
+
+impl XmlValue {
+    /// renders the value. `substitute` returns the text of a substitution,
+    /// which will be escaped.
+    fn render<F: Fn(u16) -> Option<String>>(&self, out: &mut String, substitute: &F) {
+        match self {
+            XmlValue::Text(text) => escape(text, out),
+            XmlValue::CharRef(c) => out.push_str(&format!("&#x{:04x};", c)),
+            XmlValue::EntityRef(name) => out.push_str(&format!("&{};", name)),
+            XmlValue::Substitution { index, .. } => {
+                if let Some(value) = substitute(*index) {
+                    escape(&value, out)
+                }
+            }
+        }
+    }
+
+    /// returns `true` if this is an optional substitution, whose value is missing or empty
+    fn is_omitted<F: Fn(u16) -> Option<String>>(&self, substitute: &F) -> bool {
+        match self {
+            XmlValue::Substitution { index, optional: true, .. } => {
+                substitute(*index).map(|v| v.is_empty()).unwrap_or(true)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl XmlNode {
+    /// renders the node as XML. `substitute` returns the text of a substitution.
+    pub fn render<F: Fn(u16) -> Option<String>>(&self, out: &mut String, substitute: &F) {
+        match self {
+            XmlNode::Element(element) => {
+                out.push('<');
+                out.push_str(&element.name);
+                for attribute in element.attributes.iter() {
+                    if attribute.value.iter().any(|v| v.is_omitted(substitute)) {
+                        continue;
+                    }
+                    out.push(' ');
+                    out.push_str(&attribute.name);
+                    out.push_str("=\"");
+                    for value in attribute.value.iter() {
+                        value.render(out, substitute);
+                    }
+                    out.push('"');
+                }
+                if element.children.is_empty() {
+                    out.push_str("/>");
+                } else {
+                    out.push('>');
+                    for child in element.children.iter() {
+                        child.render(out, substitute);
+                    }
+                    out.push_str("</");
+                    out.push_str(&element.name);
+                    out.push('>');
+                }
+            }
+            XmlNode::Value(value) => value.render(out, substitute),
+            XmlNode::CData(text) => {
+                out.push_str("<![CDATA[");
+                out.push_str(text);
+                out.push_str("]]>");
+            }
+            XmlNode::ProcessingInstruction { target, data } => {
+                out.push_str(&format!("<?{} {}?>", target, data));
+            }
+        }
+    }
+}
+
+/// parses the binary XML of a template. Names are stored inline, and not referenced by offsets
+/// (as it is done in EVTX files).
+pub(crate) struct BinXmlParser<'data> {
+    data: &'data [u8],
+    offset: usize,
+}
+
+impl<'data> BinXmlParser<'data> {
+    pub fn new(data: &'data [u8], offset: usize) -> Self {
+        Self { data, offset }
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        let value = bytes_at(self.data, self.offset, 1)?[0];
+        self.offset += 1;
+        Ok(value)
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let value = LittleEndian::read_u16(bytes_at(self.data, self.offset, 2)?);
+        self.offset += 2;
+        Ok(value)
+    }
+
+    fn peek(&self) -> Result<u8> {
+        Ok(bytes_at(self.data, self.offset, 1)?[0])
+    }
+
+    fn utf16(&mut self, characters: usize) -> Result<String> {
+        let value = utf16_from_slice(self.data, self.offset, characters)?;
+        self.offset += characters * 2;
+        Ok(value)
+    }
+
+    /// reads a name, which consists of a hash, the number of characters,
+    /// and a NUL-terminated UTF-16 string
+    fn name(&mut self) -> Result<String> {
+        let _hash = self.u16()?;
+        let characters = self.u16()? as usize;
+        let name = self.utf16(characters)?;
+        let _nul = self.u16()?;
+        Ok(name)
+    }
+
+    fn invalid(&self, reason: String) -> Error {
+        Error::InvalidData { offset: self.offset, reason }
+    }
+
+    /// reads a value, a substitution or a reference, if one of these follows
+    fn value(&mut self) -> Result<Option<XmlValue>> {
+        let token = self.peek()?;
+        let value = match token & !TOKEN_HAS_MORE_DATA {
+            TOKEN_VALUE => {
+                self.offset += 1;
+                let value_type = self.u8()?;
+                if value_type != VALUE_TYPE_STRING {
+                    return Err(self.invalid(format!("unsupported value type 0x{:02x}", value_type)));
+                }
+                let characters = self.u16()? as usize;
+                XmlValue::Text(self.utf16(characters)?)
+            }
+            TOKEN_CHAR_REF => {
+                self.offset += 1;
+                XmlValue::CharRef(self.u16()?)
+            }
+            TOKEN_ENTITY_REF => {
+                self.offset += 1;
+                XmlValue::EntityRef(self.name()?)
+            }
+            TOKEN_NORMAL_SUBSTITUTION | TOKEN_OPTIONAL_SUBSTITUTION => {
+                self.offset += 1;
+                let index = self.u16()?;
+                let value_type = self.u8()?;
+                XmlValue::Substitution {
+                    index,
+                    value_type,
+                    optional: token & !TOKEN_HAS_MORE_DATA == TOKEN_OPTIONAL_SUBSTITUTION,
+                }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(value))
+    }
+
+    /// parses an element, whose `TOKEN_OPEN_START_ELEMENT` has not been read yet
+    fn element(&mut self, depth: usize) -> Result<XmlElement> {
+        if depth > MAX_DEPTH {
+            return Err(self.invalid("binary XML is nested too deeply".to_string()));
+        }
+        let token = self.u8()?;
+        let _dependency_id = self.u16()?;
+        let _size = bytes_at(self.data, self.offset, 4)?;
+        self.offset += 4;
+        let name = self.name()?;
+
+        let mut attributes = Vec::new();
+        if token & TOKEN_HAS_MORE_DATA != 0 {
+            let _attribute_list_size = bytes_at(self.data, self.offset, 4)?;
+            self.offset += 4;
+            while self.peek()? & !TOKEN_HAS_MORE_DATA == TOKEN_ATTRIBUTE {
+                let more = self.u8()? & TOKEN_HAS_MORE_DATA != 0;
+                let name = self.name()?;
+                let mut value = Vec::new();
+                while let Some(v) = self.value()? {
+                    value.push(v);
+                }
+                attributes.push(XmlAttribute { name, value });
+                if !more {
+                    break;
+                }
+            }
+        }
+
+        let mut children = Vec::new();
+        match self.u8()? {
+            TOKEN_CLOSE_EMPTY_ELEMENT => (),
+            TOKEN_CLOSE_START_ELEMENT => loop {
+                if self.peek()? == TOKEN_END_ELEMENT {
+                    self.offset += 1;
+                    break;
+                }
+                children.push(self.node(depth + 1)?);
+            },
+            token => return Err(self.invalid(format!("unexpected token 0x{:02x} in element {}", token, name))),
+        }
+
+        Ok(XmlElement { name, attributes, children })
+    }
+
+    /// parses the content of an element
+    fn node(&mut self, depth: usize) -> Result<XmlNode> {
+        if let Some(value) = self.value()? {
+            return Ok(XmlNode::Value(value));
+        }
+        let token = self.peek()?;
+        match token & !TOKEN_HAS_MORE_DATA {
+            TOKEN_OPEN_START_ELEMENT => Ok(XmlNode::Element(self.element(depth)?)),
+            TOKEN_CDATA_SECTION => {
+                self.offset += 1;
+                let characters = self.u16()? as usize;
+                Ok(XmlNode::CData(self.utf16(characters)?))
+            }
+            TOKEN_PI_TARGET => {
+                self.offset += 1;
+                let target = self.name()?;
+                if self.u8()? != TOKEN_PI_DATA {
+                    return Err(self.invalid("processing instruction without data".to_string()));
+                }
+                let characters = self.u16()? as usize;
+                let data = self.utf16(characters)?;
+                Ok(XmlNode::ProcessingInstruction { target, data })
+            }
+            _ => Err(self.invalid(format!("unexpected token 0x{:02x}", token))),
+        }
+    }
+
+    /// parses a fragment, which ends with `TOKEN_EOF`
+    pub fn fragment(&mut self) -> Result<Vec<XmlNode>> {
+        let mut nodes = Vec::new();
+        loop {
+            match self.peek()? {
+                TOKEN_EOF => return Ok(nodes),
+                TOKEN_FRAGMENT_HEADER => {
+                    // major version, minor version and flags
+                    bytes_at(self.data, self.offset, 4)?;
+                    self.offset += 4;
+                }
+                _ => nodes.push(self.node(0)?),
+            }
+        }
+    }
+}
@@ -0,0 +1,530 @@
+//! parses the `WEVT_TEMPLATE` resource, which contains the event definitions
+//! of manifest-based event providers.
+//!
+//! All offsets in the resource are relative to its start (the `CRIM` header).
+
+mod binxml;
+pub use binxml::{XmlAttribute, XmlElement, XmlNode, XmlValue};
+
+use byteorder::{ByteOrder, LittleEndian};
+use std::fmt;
+use crate::error::{Error, Result};
+use crate::utils::{bytes_at, utf16_from_slice};
+use binxml::BinXmlParser;
+
+/// message identifier which means that there is no message
+const NO_MESSAGE: u32 = 0xffff_ffff;
+
+fn u16_at(data: &[u8], offset: usize) -> Result<u16> {
+    Ok(LittleEndian::read_u16(bytes_at(data, offset, 2)?))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<u32> {
+    Ok(LittleEndian::read_u32(bytes_at(data, offset, 4)?))
+}
+
+fn u64_at(data: &[u8], offset: usize) -> Result<u64> {
+    Ok(LittleEndian::read_u64(bytes_at(data, offset, 8)?))
+}
+
+fn message_id_at(data: &[u8], offset: usize) -> Result<Option<u32>> {
+    match u32_at(data, offset)? {
+        NO_MESSAGE => Ok(None),
+        id => Ok(Some(id)),
+    }
+}
+
+/// reads a string which is prefixed by its size in bytes (including the size itself)
+/// and terminated by NUL. An offset of 0 means that there is no name.
+fn name_at(data: &[u8], offset: u32) -> Result<String> {
+    if offset == 0 {
+        return Ok(String::new());
+    }
+    let offset = offset as usize;
+    let size = u32_at(data, offset)? as usize;
+    let characters = size.saturating_sub(4) / 2;
+    let name = utf16_from_slice(data, offset + 4, characters)?;
+    Ok(name.trim_end_matches('\0').to_string())
+}
+
+/// verifies that the data at `offset` starts with `signature`, and returns the offset after it
+fn expect_signature(data: &[u8], offset: usize, signature: &[u8; 4]) -> Result<usize> {
+    let found = bytes_at(data, offset, 4)?;
+    if found != signature {
+        return Err(Error::InvalidData {
+            offset,
+            reason: format!(
+                "expected signature {}, found {:?}",
+                String::from_utf8_lossy(signature),
+                found
+            ),
+        });
+    }
+    Ok(offset + 4)
+}
+
+/// a GUID, as stored in Windows structures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Guid(pub [u8; 16]);
+
+impl Guid {
+    fn at(data: &[u8], offset: usize) -> Result<Self> {
+        let mut guid = [0u8; 16];
+        guid.copy_from_slice(bytes_at(data, offset, 16)?);
+        Ok(Guid(guid))
+    }
+}
+
+/// displays the GUID in registry format, e.g. `{54849625-5478-4994-a5ba-3e3b0328c30d}`
+impl fmt::Display for Guid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let g = &self.0;
+        write!(
+            f,
+            "{{{:08x}-{:04x}-{:04x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}}}",
+            LittleEndian::read_u32(&g[0..4]),
+            LittleEndian::read_u16(&g[4..6]),
+            LittleEndian::read_u16(&g[6..8]),
+            g[8], g[9], g[10], g[11], g[12], g[13], g[14], g[15]
+        )
+    }
+}
+
+/// the contents of a `WEVT_TEMPLATE` resource
+#[derive(Debug, Clone)]
+pub struct Manifest {
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub providers: Vec<Provider>,
+}
+
+/// an event provider and all its definitions
+#[derive(Debug, Clone, Default)]
+pub struct Provider {
+    pub guid: Option<Guid>,
+    pub message_id: Option<u32>,
+    pub channels: Vec<Channel>,
+    pub events: Vec<Event>,
+    pub levels: Vec<Level>,
+    pub tasks: Vec<Task>,
+    pub opcodes: Vec<Opcode>,
+    pub keywords: Vec<Keyword>,
+    pub maps: Vec<ValueMap>,
+    pub templates: Vec<Template>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Channel {
+    pub id: u32,
+    pub name: String,
+    pub message_id: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub id: u16,
+    pub version: u8,
+    pub channel: u8,
+    pub level: u8,
+    pub opcode: u8,
+    pub task: u16,
+    pub keywords: u64,
+    pub message_id: Option<u32>,
+    pub flags: u32,
+
+    /// offsets of the definitions which are used by this event, or 0
+    pub template_offset: u32,
+    pub opcode_offset: u32,
+    pub level_offset: u32,
+    pub task_offset: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Level {
+    /// offset of the definition, which is referenced by events
+    pub offset: u32,
+    pub value: u32,
+    pub name: String,
+    pub message_id: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Task {
+    /// offset of the definition, which is referenced by events
+    pub offset: u32,
+    pub value: u32,
+    pub name: String,
+    pub message_id: Option<u32>,
+    pub guid: Guid,
+}
+
+#[derive(Debug, Clone)]
+pub struct Opcode {
+    /// offset of the definition, which is referenced by events
+    pub offset: u32,
+
+    /// the opcode in the lower 16 bits; task specific opcodes contain the task in the upper 16 bits
+    pub value: u32,
+    pub name: String,
+    pub message_id: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Keyword {
+    pub mask: u64,
+    pub name: String,
+    pub message_id: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueMapKind {
+    /// every value is mapped to a message (`VMAP`)
+    Value,
+
+    /// every bit of the value is mapped to a message (`BMAP`)
+    Bitmap,
+}
+
+#[derive(Debug, Clone)]
+pub struct ValueMapEntry {
+    pub value: u32,
+    pub message_id: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ValueMap {
+    pub kind: ValueMapKind,
+    pub name: String,
+    pub entries: Vec<ValueMapEntry>,
+}
+
+/// describes a value which is stored in an event record
+#[derive(Debug, Clone)]
+pub struct TemplateItem {
+    pub name: String,
+    pub input_type: u8,
+    pub output_type: u8,
+
+    /// the number of values, if this is an array
+    pub count: u16,
+
+    /// the size of the value, if it has a fixed size
+    pub length: u16,
+}
+
+/// the layout of the XML representation of an event, with placeholders for the values
+#[derive(Debug, Clone)]
+pub struct Template {
+    /// offset of the definition, which is referenced by events
+    pub offset: u32,
+    pub guid: Guid,
+    pub items: Vec<TemplateItem>,
+    pub nodes: Vec<XmlNode>,
+}
+
+impl Template {
+    /// renders the template as XML, replacing the substitutions by `values`.
+    /// Missing values are rendered as empty strings.
+    pub fn render(&self, values: &[String]) -> String {
+        let mut out = String::new();
+        let substitute = |index: u16| values.get(index as usize).cloned();
+        for node in self.nodes.iter() {
+            node.render(&mut out, &substitute);
+        }
+        out
+    }
+
+    /// renders the template as XML, with substitutions rendered as `$(index)`
+    pub fn to_xml(&self) -> String {
+        let mut out = String::new();
+        let substitute = |index: u16| Some(format!("$({})", index));
+        for node in self.nodes.iter() {
+            node.render(&mut out, &substitute);
+        }
+        out
+    }
+}
+
+impl Provider {
+    /// returns the template which is used by `event`
+    pub fn template_of(&self, event: &Event) -> Option<&Template> {
+        self.templates.iter().find(|t| t.offset == event.template_offset && t.offset != 0)
+    }
+
+    /// returns the level of `event`
+    pub fn level_of(&self, event: &Event) -> Option<&Level> {
+        self.levels.iter().find(|l| l.offset == event.level_offset && l.offset != 0)
+    }
+
+    /// returns the task of `event`
+    pub fn task_of(&self, event: &Event) -> Option<&Task> {
+        self.tasks.iter().find(|t| t.offset == event.task_offset && t.offset != 0)
+    }
+
+    /// returns the opcode of `event`
+    pub fn opcode_of(&self, event: &Event) -> Option<&Opcode> {
+        self.opcodes.iter().find(|o| o.offset == event.opcode_offset && o.offset != 0)
+    }
+
+    /// returns the channel of `event`
+    pub fn channel_of(&self, event: &Event) -> Option<&Channel> {
+        self.channels.iter().find(|c| c.id == event.channel as u32)
+    }
+
+    /// returns the value map with the given name
+    pub fn map(&self, name: &str) -> Option<&ValueMap> {
+        self.maps.iter().find(|m| m.name == name)
+    }
+}
+
+impl Manifest {
+    /// parses the contents of a `WEVT_TEMPLATE` resource
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let offset = expect_signature(data, 0, b"CRIM")?;
+        let major_version = u16_at(data, offset + 4)?;
+        let minor_version = u16_at(data, offset + 6)?;
+        let number_of_providers = u32_at(data, offset + 8)? as usize;
+
+        let mut providers = Vec::new();
+        for idx in 0..number_of_providers {
+            let descriptor = 16 + idx * 20;
+            let guid = Guid::at(data, descriptor)?;
+            let provider_offset = u32_at(data, descriptor + 16)? as usize;
+            let mut provider = read_provider(data, provider_offset)?;
+            provider.guid = Some(guid);
+            providers.push(provider);
+        }
+
+        Ok(Manifest {
+            major_version,
+            minor_version,
+            providers,
+        })
+    }
+}
+
+fn read_provider(data: &[u8], offset: usize) -> Result<Provider> {
+    let offset = expect_signature(data, offset, b"WEVT")?;
+    let mut provider = Provider {
+        message_id: message_id_at(data, offset + 4)?,
+        ..Default::default()
+    };
+    let number_of_elements = u32_at(data, offset + 8)? as usize;
+
+    for idx in 0..number_of_elements {
+        let element_offset = u32_at(data, offset + 16 + idx * 8)? as usize;
+        let signature = bytes_at(data, element_offset, 4)?;
+        let element = element_offset + 8;
+        match signature {
+            b"CHAN" => provider.channels = read_channels(data, element)?,
+            b"EVNT" => provider.events = read_events(data, element)?,
+            b"LEVL" => provider.levels = read_levels(data, element)?,
+            b"TASK" => provider.tasks = read_tasks(data, element)?,
+            b"OPCO" => provider.opcodes = read_levels(data, element)?
+                .into_iter()
+                .map(|l| Opcode {
+                    offset: l.offset,
+                    value: l.value,
+                    name: l.name,
+                    message_id: l.message_id,
+                })
+                .collect(),
+            b"KEYW" => provider.keywords = read_keywords(data, element)?,
+            b"MAPS" => provider.maps = read_maps(data, element_offset)?,
+            b"TTBL" => provider.templates = read_templates(data, element)?,
+            _ => log::debug!(
+                "ignoring unknown provider element {:?} at 0x{:08x}",
+                String::from_utf8_lossy(signature),
+                element_offset
+            ),
+        }
+    }
+    Ok(provider)
+}
+
+/// `offset` points to the number of definitions, which follow
+fn read_channels(data: &[u8], offset: usize) -> Result<Vec<Channel>> {
+    let count = u32_at(data, offset)? as usize;
+    let mut channels = Vec::new();
+    for idx in 0..count {
+        let definition = offset + 4 + idx * 16;
+        channels.push(Channel {
+            id: u32_at(data, definition)?,
+            name: name_at(data, u32_at(data, definition + 4)?)?,
+            message_id: message_id_at(data, definition + 12)?,
+        });
+    }
+    Ok(channels)
+}
+
+fn read_events(data: &[u8], offset: usize) -> Result<Vec<Event>> {
+    let count = u32_at(data, offset)? as usize;
+    let mut events = Vec::new();
+    for idx in 0..count {
+        let definition = offset + 8 + idx * 48;
+        let header = bytes_at(data, definition, 48)?;
+        events.push(Event {
+            id: LittleEndian::read_u16(&header[0..2]),
+            version: header[2],
+            channel: header[3],
+            level: header[4],
+            opcode: header[5],
+            task: LittleEndian::read_u16(&header[6..8]),
+            keywords: LittleEndian::read_u64(&header[8..16]),
+            message_id: message_id_at(header, 16)?,
+            template_offset: LittleEndian::read_u32(&header[20..24]),
+            opcode_offset: LittleEndian::read_u32(&header[24..28]),
+            level_offset: LittleEndian::read_u32(&header[28..32]),
+            task_offset: LittleEndian::read_u32(&header[32..36]),
+            flags: LittleEndian::read_u32(&header[44..48]),
+        });
+    }
+    Ok(events)
+}
+
+fn read_levels(data: &[u8], offset: usize) -> Result<Vec<Level>> {
+    let count = u32_at(data, offset)? as usize;
+    let mut levels = Vec::new();
+    for idx in 0..count {
+        let definition = offset + 4 + idx * 12;
+        levels.push(Level {
+            offset: definition as u32,
+            value: u32_at(data, definition)?,
+            message_id: message_id_at(data, definition + 4)?,
+            name: name_at(data, u32_at(data, definition + 8)?)?,
+        });
+    }
+    Ok(levels)
+}
+
+fn read_tasks(data: &[u8], offset: usize) -> Result<Vec<Task>> {
+    let count = u32_at(data, offset)? as usize;
+    let mut tasks = Vec::new();
+    for idx in 0..count {
+        let definition = offset + 4 + idx * 28;
+        tasks.push(Task {
+            offset: definition as u32,
+            value: u32_at(data, definition)?,
+            message_id: message_id_at(data, definition + 4)?,
+            guid: Guid::at(data, definition + 8)?,
+            name: name_at(data, u32_at(data, definition + 24)?)?,
+        });
+    }
+    Ok(tasks)
+}
+
+fn read_keywords(data: &[u8], offset: usize) -> Result<Vec<Keyword>> {
+    let count = u32_at(data, offset)? as usize;
+    let mut keywords = Vec::new();
+    for idx in 0..count {
+        let definition = offset + 4 + idx * 16;
+        keywords.push(Keyword {
+            mask: u64_at(data, definition)?,
+            message_id: message_id_at(data, definition + 8)?,
+            name: name_at(data, u32_at(data, definition + 12)?)?,
+        });
+    }
+    Ok(keywords)
+}
+
+fn read_map(data: &[u8], offset: usize) -> Result<ValueMap> {
+    let kind = match bytes_at(data, offset, 4)? {
+        b"VMAP" => ValueMapKind::Value,
+        b"BMAP" => ValueMapKind::Bitmap,
+        signature => {
+            return Err(Error::InvalidData {
+                offset,
+                reason: format!("expected a value map, found {:?}", signature),
+            })
+        }
+    };
+    let name = name_at(data, u32_at(data, offset + 8)?)?;
+    let count = u32_at(data, offset + 16)? as usize;
+    let mut entries = Vec::new();
+    for idx in 0..count {
+        let entry = offset + 20 + idx * 8;
+        entries.push(ValueMapEntry {
+            value: u32_at(data, entry)?,
+            message_id: message_id_at(data, entry + 4)?,
+        });
+    }
+    Ok(ValueMap { kind, name, entries })
+}
+
+/// `offset` points to the `MAPS` signature
+fn read_maps(data: &[u8], offset: usize) -> Result<Vec<ValueMap>> {
+    let count = u32_at(data, offset + 8)? as usize;
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+
+    // the offsets of the maps follow the header. Usually, the offset of the first map
+    // is omitted, because it directly follows the offsets of the other maps.
+    let offsets_start = offset + 12;
+    let first_map = offsets_start + (count - 1) * 4;
+    let mut offsets = Vec::new();
+    let explicit = match bytes_at(data, first_map, 4)? {
+        b"VMAP" | b"BMAP" => {
+            offsets.push(first_map);
+            count - 1
+        }
+        _ => count,
+    };
+    for idx in 0..explicit {
+        offsets.push(u32_at(data, offsets_start + idx * 4)? as usize);
+    }
+
+    offsets.into_iter().map(|o| read_map(data, o)).collect()
+}
+
+fn read_templates(data: &[u8], offset: usize) -> Result<Vec<Template>> {
+    let count = u32_at(data, offset)? as usize;
+    let mut templates = Vec::new();
+    let mut template_offset = offset + 4;
+    for _ in 0..count {
+        let (template, size) = read_template(data, template_offset)?;
+        templates.push(template);
+        template_offset += size;
+    }
+    Ok(templates)
+}
+
+/// returns the template and its size
+fn read_template(data: &[u8], offset: usize) -> Result<(Template, usize)> {
+    expect_signature(data, offset, b"TEMP")?;
+    let size = u32_at(data, offset + 4)? as usize;
+    if size < 40 {
+        return Err(Error::InvalidData {
+            offset,
+            reason: format!("template is too small: {} bytes", size),
+        });
+    }
+    let number_of_items = u32_at(data, offset + 8)? as usize;
+    let items_offset = u32_at(data, offset + 16)? as usize;
+    let guid = Guid::at(data, offset + 24)?;
+
+    let template_data = bytes_at(data, 0, offset + size)?;
+    let nodes = BinXmlParser::new(template_data, offset + 40).fragment()?;
+
+    let mut items = Vec::new();
+    for idx in 0..number_of_items {
+        let item = bytes_at(data, items_offset + idx * 20, 20)?;
+        items.push(TemplateItem {
+            input_type: item[4],
+            output_type: item[5],
+            count: LittleEndian::read_u16(&item[12..14]),
+            length: LittleEndian::read_u16(&item[14..16]),
+            name: name_at(data, LittleEndian::read_u32(&item[16..20]))?,
+        });
+    }
+
+    Ok((
+        Template {
+            offset: offset as u32,
+            guid,
+            items,
+            nodes,
+        },
+        size,
+    ))
+}
@@ -0,0 +1,42 @@
+use from_bytes::*;
+use from_bytes_derive::*;
+use packed_struct::prelude::*;
+
+/// header of OS/2 style bitmaps, which is followed by a palette of RGBTRIPLEs
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(bit_numbering = "msb0", endian = "lsb")]
+pub struct BITMAPCOREHEADER {
+    pub bcSize: u32,
+    pub bcWidth: u16,
+    pub bcHeight: u16,
+    pub bcPlanes: u16,
+    pub bcBitCount: u16,
+}
+
+/// the common part of BITMAPINFOHEADER, BITMAPV4HEADER and BITMAPV5HEADER,
+/// which is followed by a palette of RGBQUADs
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(bit_numbering = "msb0", endian = "lsb")]
+pub struct BITMAPINFOHEADER {
+    pub biSize: u32,
+    pub biWidth: i32,
+    pub biHeight: i32,  /* negative for top-down bitmaps */
+    pub biPlanes: u16,
+    pub biBitCount: u16,
+    pub biCompression: u32,
+    pub biSizeImage: u32,
+    pub biXPelsPerMeter: i32,
+    pub biYPelsPerMeter: i32,
+    pub biClrUsed: u32,
+    pub biClrImportant: u32,
+}
+
+pub const BI_BITFIELDS: u32 = 3;
+pub const BI_ALPHABITFIELDS: u32 = 6;
+
+pub const BITMAPINFOHEADER_SIZE: u32 = 40;
+pub const BITMAPV2INFOHEADER_SIZE: u32 = 52;
+pub const BITMAPV3INFOHEADER_SIZE: u32 = 56;
+pub const OS2BITMAPHEADER2_SIZE: u32 = 64;
+pub const BITMAPV4HEADER_SIZE: u32 = 108;
+pub const BITMAPV5HEADER_SIZE: u32 = 124;
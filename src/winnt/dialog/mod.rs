@@ -0,0 +1,64 @@
+use from_bytes::*;
+use from_bytes_derive::*;
+use packed_struct::prelude::*;
+
+/// header of a classic dialog template, which is followed by the menu, the window class,
+/// the title, the font (if `DS_SETFONT` is set) and `cdit` DLGITEMTEMPLATEs
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(bit_numbering = "msb0", endian = "lsb")]
+pub struct DLGTEMPLATE {
+    pub style: u32,
+    pub dwExtendedStyle: u32,
+    pub cdit: u16,
+    pub x: i16,
+    pub y: i16,
+    pub cx: i16,
+    pub cy: i16,
+}
+
+/// a control of a classic dialog template, which is followed by the window class,
+/// the title and the creation data
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(bit_numbering = "msb0", endian = "lsb")]
+pub struct DLGITEMTEMPLATE {
+    pub style: u32,
+    pub dwExtendedStyle: u32,
+    pub x: i16,
+    pub y: i16,
+    pub cx: i16,
+    pub cy: i16,
+    pub id: u16,
+}
+
+/// header of an extended dialog template, which is followed by the same
+/// variable length fields as a DLGTEMPLATE
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(bit_numbering = "msb0", endian = "lsb")]
+pub struct DLGTEMPLATEEX {
+    pub dlgVer: u16,
+    pub signature: u16, /* always 0xffff */
+    pub helpID: u32,
+    pub exStyle: u32,
+    pub style: u32,
+    pub cDlgItems: u16,
+    pub x: i16,
+    pub y: i16,
+    pub cx: i16,
+    pub cy: i16,
+}
+
+/// a control of an extended dialog template
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(bit_numbering = "msb0", endian = "lsb")]
+pub struct DLGITEMTEMPLATEEX {
+    pub helpID: u32,
+    pub exStyle: u32,
+    pub style: u32,
+    pub x: i16,
+    pub y: i16,
+    pub cx: i16,
+    pub cy: i16,
+    pub id: u32,
+}
+
+pub const DS_SETFONT: u32 = 0x40;
@@ -0,0 +1,45 @@
+use from_bytes::*;
+use from_bytes_derive::*;
+use packed_struct::prelude::*;
+
+/// an entry of an `RT_FONTDIR` resource, which is preceded by the ordinal of the
+/// `RT_FONT` resource and followed by the NUL-terminated device and face names
+#[derive(PackedStruct, Debug, StructFromBytes, Clone, Copy)]
+#[packed_struct(bit_numbering = "msb0", endian = "lsb")]
+pub struct FONTDIRENTRY {
+    pub dfVersion: u16,
+    pub dfSize: u32,
+    pub dfCopyright: [u8; 60],
+    pub dfType: u16,
+    pub dfPoints: u16,
+    pub dfVertRes: u16,
+    pub dfHorizRes: u16,
+    pub dfAscent: u16,
+    pub dfInternalLeading: u16,
+    pub dfExternalLeading: u16,
+    pub dfItalic: u8,
+    pub dfUnderline: u8,
+    pub dfStrikeOut: u8,
+    pub dfWeight: u16,
+    pub dfCharSet: u8,
+    pub dfPixWidth: u16,
+    pub dfPixHeight: u16,
+    pub dfPitchAndFamily: u8,
+    pub dfAvgWidth: u16,
+    pub dfMaxWidth: u16,
+    pub dfFirstChar: u8,
+    pub dfLastChar: u8,
+    pub dfDefaultChar: u8,
+    pub dfBreakChar: u8,
+    pub dfWidthBytes: u16,
+    pub dfDevice: u32,      /* offset of the device name in the font file */
+    pub dfFace: u32,        /* offset of the face name in the font file */
+    pub dfReserved: u32,
+}
+
+/// the derived `PackedSize` does not support arrays
+impl PackedSize for FONTDIRENTRY {
+    fn packed_size() -> usize {
+        std::mem::size_of::<<Self as PackedStruct>::ByteArray>()
+    }
+}
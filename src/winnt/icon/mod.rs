@@ -0,0 +1,41 @@
+use from_bytes::*;
+use from_bytes_derive::*;
+use packed_struct::prelude::*;
+
+/// header of an `RT_GROUP_ICON` or `RT_GROUP_CURSOR` resource, which is followed by `idCount` entries
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(bit_numbering = "msb0", endian = "lsb")]
+pub struct GRPICONDIR {
+    pub idReserved: u16,
+    pub idType: u16,    /* 1 for icons, 2 for cursors */
+    pub idCount: u16,
+}
+
+/// entry of an `RT_GROUP_ICON` resource, which refers to the `RT_ICON` resource `nId`
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(bit_numbering = "msb0", endian = "lsb")]
+pub struct GRPICONDIRENTRY {
+    pub bWidth: u8,     /* 0 means 256 pixels */
+    pub bHeight: u8,    /* 0 means 256 pixels */
+    pub bColorCount: u8,
+    pub bReserved: u8,
+    pub wPlanes: u16,
+    pub wBitCount: u16,
+    pub dwBytesInRes: u32,
+    pub nId: u16,
+}
+
+/// entry of an `RT_GROUP_CURSOR` resource, which refers to the `RT_CURSOR` resource `nId`
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(bit_numbering = "msb0", endian = "lsb")]
+pub struct GRPCURSORDIRENTRY {
+    pub wWidth: u16,
+    pub wHeight: u16,   /* height of the XOR mask and the AND mask */
+    pub wPlanes: u16,
+    pub wBitCount: u16,
+    pub dwBytesInRes: u32,  /* including the hotspot */
+    pub nId: u16,
+}
+
+pub const RES_ICON: u16 = 1;
+pub const RES_CURSOR: u16 = 2;
@@ -0,0 +1,21 @@
+use packed_struct::prelude::*;
+use from_bytes::*;
+use from_bytes_derive::*;
+
+/// header of a block of base relocations, which is followed by
+/// `(SizeOfBlock - 8) / 2` entries of 16 bit each
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(endian="lsb")]
+pub struct IMAGE_BASE_RELOCATION {
+    pub VirtualAddress: u32, /* RVA of the page to which the relocations apply */
+    pub SizeOfBlock: u32,    /* size of the block, including this header */
+}
+
+pub const IMAGE_REL_BASED_ABSOLUTE: u8 = 0;
+pub const IMAGE_REL_BASED_HIGH: u8 = 1;
+pub const IMAGE_REL_BASED_LOW: u8 = 2;
+pub const IMAGE_REL_BASED_HIGHLOW: u8 = 3;
+pub const IMAGE_REL_BASED_HIGHADJ: u8 = 4;
+pub const IMAGE_REL_BASED_ARM_MOV32: u8 = 5;
+pub const IMAGE_REL_BASED_THUMB_MOV32: u8 = 7;
+pub const IMAGE_REL_BASED_DIR64: u8 = 10;
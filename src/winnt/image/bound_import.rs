@@ -0,0 +1,19 @@
+use packed_struct::prelude::*;
+use from_bytes::*;
+use from_bytes_derive::*;
+
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(endian="lsb")]
+pub struct IMAGE_BOUND_IMPORT_DESCRIPTOR {
+    pub TimeDateStamp: u32,
+    pub OffsetModuleName: u16,            /* relative to the start of the bound import directory */
+    pub NumberOfModuleForwarderRefs: u16, /* number of IMAGE_BOUND_FORWARDER_REFs which follow */
+}
+
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(endian="lsb")]
+pub struct IMAGE_BOUND_FORWARDER_REF {
+    pub TimeDateStamp: u32,
+    pub OffsetModuleName: u16,
+    pub Reserved: u16,
+}
@@ -0,0 +1,26 @@
+use packed_struct::prelude::*;
+use from_bytes::*;
+use from_bytes_derive::*;
+
+/// if this bit of `Attributes` is set, all addresses in the descriptor are RVAs.
+/// Otherwise (which has been created by Visual C++ 6.0), they are virtual addresses.
+pub const DLOAD_ATTRIBUTE_RVA: u32 = 0x00000001;
+
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(endian="lsb")]
+pub struct IMAGE_DELAYLOAD_DESCRIPTOR {
+    pub Attributes: u32,
+    pub DllNameRVA: u32,
+    pub ModuleHandleRVA: u32,
+    pub ImportAddressTableRVA: u32,
+    pub ImportNameTableRVA: u32,
+    pub BoundImportAddressTableRVA: u32,
+    pub UnloadInformationTableRVA: u32,
+    pub TimeDateStamp: u32,
+}
+
+impl IMAGE_DELAYLOAD_DESCRIPTOR {
+    pub fn is_rva_based(&self) -> bool {
+        self.Attributes & DLOAD_ATTRIBUTE_RVA != 0
+    }
+}
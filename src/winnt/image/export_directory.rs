@@ -0,0 +1,19 @@
+use packed_struct::prelude::*;
+use from_bytes::*;
+use from_bytes_derive::*;
+
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(endian="lsb")]
+pub struct IMAGE_EXPORT_DIRECTORY {
+    pub Characteristics: u32,
+    pub TimeDateStamp: u32,
+    pub MajorVersion: u16,
+    pub MinorVersion: u16,
+    pub Name: u32,                  /* RVA of the DLL name */
+    pub Base: u32,                  /* ordinal of the first entry in the address table */
+    pub NumberOfFunctions: u32,
+    pub NumberOfNames: u32,
+    pub AddressOfFunctions: u32,    /* RVA of the export address table */
+    pub AddressOfNames: u32,        /* RVA of the name pointer table */
+    pub AddressOfNameOrdinals: u32, /* RVA of the ordinal table */
+}
@@ -3,23 +3,61 @@ use packed_struct::prelude::*;
 use from_bytes::*;
 use from_bytes_derive::*;
 use num_derive::FromPrimitive;
+use num_traits::FromPrimitive as _;
 
 #[derive(PrimitiveEnum_u16, PackedSize_u16, FromPrimitive, Clone, Copy, PartialEq, Debug)]
 pub enum IMAGE_FILE_HEADER_Machine {
-    IMAGE_FILE_MACHINE_I386  = 0x014c,
-    IMAGE_FILE_MACHINE_IA64  = 0x0200,
-    IMAGE_FILE_MACHINE_AMD64 = 0x8664
+    IMAGE_FILE_MACHINE_UNKNOWN     = 0x0000,
+    IMAGE_FILE_MACHINE_I386        = 0x014c,
+    IMAGE_FILE_MACHINE_R4000       = 0x0166,
+    IMAGE_FILE_MACHINE_WCEMIPSV2   = 0x0169,
+    IMAGE_FILE_MACHINE_SH3         = 0x01a2,
+    IMAGE_FILE_MACHINE_SH4         = 0x01a6,
+    IMAGE_FILE_MACHINE_ARM         = 0x01c0,
+    IMAGE_FILE_MACHINE_THUMB       = 0x01c2,
+    IMAGE_FILE_MACHINE_ARMNT       = 0x01c4,
+    IMAGE_FILE_MACHINE_POWERPC     = 0x01f0,
+    IMAGE_FILE_MACHINE_IA64        = 0x0200,
+    IMAGE_FILE_MACHINE_MIPS16      = 0x0266,
+    IMAGE_FILE_MACHINE_EBC         = 0x0ebc,
+    IMAGE_FILE_MACHINE_RISCV32     = 0x5032,
+    IMAGE_FILE_MACHINE_RISCV64     = 0x5064,
+    IMAGE_FILE_MACHINE_LOONGARCH64 = 0x6264,
+    IMAGE_FILE_MACHINE_AMD64       = 0x8664,
+    IMAGE_FILE_MACHINE_ARM64EC     = 0xa641,
+    IMAGE_FILE_MACHINE_ARM64X      = 0xa64e,
+    IMAGE_FILE_MACHINE_ARM64       = 0xaa64,
+}
+
+impl IMAGE_FILE_HEADER_Machine {
+    /// returns `true` for the 32bit ARM architectures, which use MOVW/MOVT relocations
+    pub fn is_arm(&self) -> bool {
+        matches!(self,
+            IMAGE_FILE_HEADER_Machine::IMAGE_FILE_MACHINE_ARM |
+            IMAGE_FILE_HEADER_Machine::IMAGE_FILE_MACHINE_THUMB |
+            IMAGE_FILE_HEADER_Machine::IMAGE_FILE_MACHINE_ARMNT)
+    }
 }
 
 #[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
 #[packed_struct(bit_numbering="msb0", endian="lsb")]
 pub struct IMAGE_FILE_HEADER {
-    #[packed_field(bits="0..16", ty="enum")]
-    pub Machine:              IMAGE_FILE_HEADER_Machine,
+    pub Machine:              u16,
     pub NumberOfSections:     u16,
     pub TimeDateStamp:        u32,
     pub PointerToSymbolTable: u32,
     pub NumberOfSymbols:      u32,
     pub SizeOfOptionalHeader: u16,
     pub Characteristics:      u16,
-}
\ No newline at end of file
+}
+impl IMAGE_FILE_HEADER {
+    /// returns the target architecture, or `None` if `Machine` has an unknown value
+    pub fn machine(&self) -> Option<IMAGE_FILE_HEADER_Machine> {
+        IMAGE_FILE_HEADER_Machine::from_u16(self.Machine)
+    }
+
+    /// returns `true` for the 32bit ARM architectures, which use MOVW/MOVT relocations
+    pub fn is_arm(&self) -> bool {
+        self.machine().is_some_and(|machine| machine.is_arm())
+    }
+}
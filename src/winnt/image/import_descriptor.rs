@@ -0,0 +1,21 @@
+use packed_struct::prelude::*;
+use from_bytes::*;
+use from_bytes_derive::*;
+
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(endian="lsb")]
+pub struct IMAGE_IMPORT_DESCRIPTOR {
+    pub OriginalFirstThunk: u32, /* RVA of the import lookup table (Characteristics) */
+    pub TimeDateStamp: u32,      /* 0 if not bound, -1 if bound (see bound import directory) */
+    pub ForwarderChain: u32,     /* -1 if no forwarders */
+    pub Name: u32,               /* RVA of the DLL name */
+    pub FirstThunk: u32,         /* RVA of the import address table */
+}
+
+impl IMAGE_IMPORT_DESCRIPTOR {
+    /// the import directory is terminated by an entry which is completely zero.
+    /// We consider an entry without name and without IAT to be the terminator.
+    pub fn is_null(&self) -> bool {
+        self.Name == 0 && self.FirstThunk == 0
+    }
+}
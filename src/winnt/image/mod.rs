@@ -11,4 +11,18 @@ pub mod optional_header;
 pub use optional_header::*;
 
 pub mod section_header;
-pub use section_header::*;
\ No newline at end of file
+pub use section_header::*;
+pub mod import_descriptor;
+pub use import_descriptor::*;
+
+pub mod export_directory;
+pub use export_directory::*;
+
+pub mod delayload_descriptor;
+pub use delayload_descriptor::*;
+
+pub mod bound_import;
+pub use bound_import::*;
+
+pub mod base_relocation;
+pub use base_relocation::*;
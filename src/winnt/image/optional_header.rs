@@ -17,6 +17,41 @@ impl IMAGE_OPTIONAL_HEADER {
       IMAGE_OPTIONAL_HEADER::x86(v)    => v.NumberOfRvaAndSizes
     }
   }
+
+  pub fn SectionAlignment(&self) -> u32 {
+    match self {
+      IMAGE_OPTIONAL_HEADER::AMD64(v)  => v.SectionAlignment,
+      IMAGE_OPTIONAL_HEADER::x86(v)    => v.SectionAlignment
+    }
+  }
+
+  pub fn FileAlignment(&self) -> u32 {
+    match self {
+      IMAGE_OPTIONAL_HEADER::AMD64(v)  => v.FileAlignment,
+      IMAGE_OPTIONAL_HEADER::x86(v)    => v.FileAlignment
+    }
+  }
+
+  pub fn SizeOfImage(&self) -> u32 {
+    match self {
+      IMAGE_OPTIONAL_HEADER::AMD64(v)  => v.SizeOfImage,
+      IMAGE_OPTIONAL_HEADER::x86(v)    => v.SizeOfImage
+    }
+  }
+
+  pub fn SizeOfHeaders(&self) -> u32 {
+    match self {
+      IMAGE_OPTIONAL_HEADER::AMD64(v)  => v.SizeOfHeaders,
+      IMAGE_OPTIONAL_HEADER::x86(v)    => v.SizeOfHeaders
+    }
+  }
+
+  pub fn ImageBase(&self) -> u64 {
+    match self {
+      IMAGE_OPTIONAL_HEADER::AMD64(v)  => v.ImageBase,
+      IMAGE_OPTIONAL_HEADER::x86(v)    => v.ImageBase as u64
+    }
+  }
 }
 
 #[derive(PrimitiveEnum_u16, PackedSize_u16, FromPrimitive, Clone, Copy, PartialEq, Debug)]
@@ -97,7 +132,7 @@ pub struct IMAGE_OPTIONAL_HEADER32 {
     pub NumberOfRvaAndSizes: u32,
 }
 
-#[derive(FromPrimitive, ToPrimitive, Debug)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum IMAGE_DIRECTORY_ENTRY {
   IMAGE_DIRECTORY_ENTRY_EXPORT = 0,
   IMAGE_DIRECTORY_ENTRY_IMPORT = 1,
@@ -2,7 +2,7 @@ use from_bytes::*;
 use from_bytes_derive::*;
 use packed_struct::prelude::*;
 
-#[derive(PackedStruct, Debug, StructFromBytes, PackedSize)]
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
 #[packed_struct(bit_numbering = "msb0", endian = "lsb")]
 pub struct IMAGE_RESOURCE_DATA_ENTRY {
     pub OffsetToData: u32,
@@ -2,6 +2,7 @@ use from_bytes::*;
 use from_bytes_derive::*;
 use packed_struct::prelude::*;
 use crate::utils::*;
+use crate::error::Result;
 
 #[derive(PackedStruct, Debug, StructFromBytes, PackedSize)]
 #[packed_struct(bit_numbering = "msb0", endian = "lsb")]
@@ -11,14 +12,15 @@ pub struct IMAGE_RESOURCE_DIRECTORY_ENTRY {
 }
 
 impl IMAGE_RESOURCE_DIRECTORY_ENTRY {
-    pub fn parse_identifier(&self, resources: &[u8]) -> EntryIdentifier {
+    pub fn parse_identifier(&self, resources: &[u8]) -> Result<EntryIdentifier> {
         if self.is_named_entry() {
             let offset_to_name = (self.Name & 0x7fffffff) as usize;
-            let Length = (resources[offset_to_name] as u16 | ((resources[offset_to_name+1] as u16)<<8 as u8)) as usize;
-            let Name = utf16_from_slice(resources, offset_to_name+2, Length);
-            EntryIdentifier::Name(Name.to_string())
+            let length_bytes = bytes_at(resources, offset_to_name, 2)?;
+            let Length = (length_bytes[0] as u16 | ((length_bytes[1] as u16)<<8)) as usize;
+            let Name = utf16_from_slice(resources, offset_to_name+2, Length)?;
+            Ok(EntryIdentifier::Name(Name))
         } else {
-            EntryIdentifier::Id((self.Name & 0x0000ffff) as u16)
+            Ok(EntryIdentifier::Id((self.Name & 0x0000ffff) as u16))
         }
     }
 
@@ -35,9 +37,18 @@ impl IMAGE_RESOURCE_DIRECTORY_ENTRY {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EntryIdentifier {
     Name(String),
     Id(u16),
     NoIdentifier,
-}
\ No newline at end of file
+}
+impl std::fmt::Display for EntryIdentifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EntryIdentifier::Name(name) => write!(f, "{}", name),
+            EntryIdentifier::Id(id) => write!(f, "#{}", id),
+            EntryIdentifier::NoIdentifier => write!(f, "<root>"),
+        }
+    }
+}
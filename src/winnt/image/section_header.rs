@@ -16,4 +16,14 @@ pub struct IMAGE_SECTION_HEADER {
     pub NumberOfRelocations: u16,
     pub NumberOfLinenumbers: u16,
     pub Characteristics: u32,
-  }
\ No newline at end of file
+  }
+
+impl IMAGE_SECTION_HEADER {
+    /// returns the name of the section, without trailing NUL bytes.
+    ///
+    /// Invalid UTF-8 sequences are replaced by `U+FFFD`
+    pub fn name(&self) -> String {
+        let len = self.Name.iter().position(|&c| c == 0).unwrap_or(self.Name.len());
+        String::from_utf8_lossy(&self.Name[..len]).to_string()
+    }
+}
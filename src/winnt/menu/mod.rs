@@ -0,0 +1,47 @@
+use from_bytes::*;
+use from_bytes_derive::*;
+use packed_struct::prelude::*;
+
+/// header of a normal menu template, which is followed by the menu items
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(bit_numbering = "msb0", endian = "lsb")]
+pub struct MENUITEMTEMPLATEHEADER {
+    pub versionNumber: u16, /* always 0 */
+    pub offset: u16,        /* offset of the first item, relative to the end of this header */
+}
+
+/// header of an extended menu template
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(bit_numbering = "msb0", endian = "lsb")]
+pub struct MENUEX_TEMPLATE_HEADER {
+    pub wVersion: u16,      /* always 1 */
+    pub wOffset: u16,       /* offset of the first item, relative to the end of this field */
+    pub dwHelpId: u32,
+}
+
+/// an entry of an `RT_ACCELERATOR` resource
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(bit_numbering = "msb0", endian = "lsb")]
+pub struct ACCELTABLEENTRY {
+    pub fFlags: u16,
+    pub wAnsi: u16,
+    pub wId: u16,
+    pub padding: u16,
+}
+
+pub const MF_POPUP: u16 = 0x0010;
+pub const MF_END: u16 = 0x0080;
+pub const MFT_SEPARATOR: u32 = 0x0800;
+
+/// flags of extended menu items
+pub const MFR_POPUP: u16 = 0x01;
+pub const MFR_END: u16 = 0x80;
+
+pub const FVIRTKEY: u16 = 0x01;
+pub const FNOINVERT: u16 = 0x02;
+pub const FSHIFT: u16 = 0x04;
+pub const FCONTROL: u16 = 0x08;
+pub const FALT: u16 = 0x10;
+
+/// marks the last entry of an accelerator table
+pub const ACCEL_LAST: u16 = 0x80;
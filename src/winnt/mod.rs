@@ -1,5 +1,22 @@
+#[allow(clippy::upper_case_acronyms)]
+pub mod bitmap;
+#[allow(clippy::upper_case_acronyms)]
+pub mod dialog;
+#[allow(clippy::upper_case_acronyms)]
+pub mod font;
+#[allow(clippy::upper_case_acronyms)]
+pub mod icon;
 pub mod image;
+#[allow(clippy::upper_case_acronyms)]
+pub mod menu;
 pub mod message;
+pub mod version;
 
+pub use bitmap::*;
+pub use dialog::*;
+pub use font::*;
+pub use icon::*;
 pub use image::*;
-pub use message::*;
\ No newline at end of file
+pub use menu::*;
+pub use message::*;
+pub use version::*;
\ No newline at end of file
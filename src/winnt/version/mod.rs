@@ -0,0 +1,40 @@
+use from_bytes::*;
+use from_bytes_derive::*;
+use packed_struct::prelude::*;
+
+pub const VS_FFI_SIGNATURE: u32 = 0xfeef04bd;
+
+/// version information which does not depend on the language
+#[derive(PackedStruct, Debug, StructFromBytes, PackedSize, Clone, Copy)]
+#[packed_struct(bit_numbering = "msb0", endian = "lsb")]
+pub struct VS_FIXEDFILEINFO {
+    pub dwSignature: u32,       /* always 0xfeef04bd */
+    pub dwStrucVersion: u32,
+    pub dwFileVersionMS: u32,
+    pub dwFileVersionLS: u32,
+    pub dwProductVersionMS: u32,
+    pub dwProductVersionLS: u32,
+    pub dwFileFlagsMask: u32,
+    pub dwFileFlags: u32,
+    pub dwFileOS: u32,
+    pub dwFileType: u32,
+    pub dwFileSubtype: u32,
+    pub dwFileDateMS: u32,
+    pub dwFileDateLS: u32,
+}
+
+impl VS_FIXEDFILEINFO {
+    /// returns the file version as `[major, minor, build, revision]`
+    pub fn file_version(&self) -> [u16; 4] {
+        split_version(self.dwFileVersionMS, self.dwFileVersionLS)
+    }
+
+    /// returns the product version as `[major, minor, build, revision]`
+    pub fn product_version(&self) -> [u16; 4] {
+        split_version(self.dwProductVersionMS, self.dwProductVersionLS)
+    }
+}
+
+fn split_version(ms: u32, ls: u32) -> [u16; 4] {
+    [(ms >> 16) as u16, ms as u16, (ls >> 16) as u16, ls as u16]
+}
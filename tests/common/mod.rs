@@ -0,0 +1,435 @@
+#![allow(dead_code)]
+
+//! helpers to create small synthetic PE images, for features which
+//! are not covered by the files in `samples/`
+
+pub const FILE_ALIGNMENT: u32 = 0x200;
+pub const SECTION_ALIGNMENT: u32 = 0x1000;
+
+pub const IMAGE_DIRECTORY_ENTRY_EXPORT: usize = 0;
+pub const IMAGE_DIRECTORY_ENTRY_IMPORT: usize = 1;
+pub const IMAGE_DIRECTORY_ENTRY_RESOURCE: usize = 2;
+pub const IMAGE_DIRECTORY_ENTRY_BASERELOC: usize = 5;
+pub const IMAGE_DIRECTORY_ENTRY_BOUND_IMPORT: usize = 11;
+pub const IMAGE_DIRECTORY_ENTRY_DELAY_IMPORT: usize = 13;
+
+fn align(value: u32, alignment: u32) -> u32 {
+    value.div_ceil(alignment) * alignment
+}
+
+/// contents of a section, which is being built
+pub struct SectionData {
+    pub virtual_address: u32,
+    pub data: Vec<u8>,
+}
+
+impl SectionData {
+    pub fn new(virtual_address: u32) -> Self {
+        Self { virtual_address, data: Vec::new() }
+    }
+
+    /// RVA of the next byte which will be appended
+    pub fn rva(&self) -> u32 {
+        self.virtual_address + self.data.len() as u32
+    }
+
+    /// appends `bytes` and returns their RVA
+    pub fn push(&mut self, bytes: &[u8]) -> u32 {
+        let rva = self.rva();
+        self.data.extend_from_slice(bytes);
+        rva
+    }
+
+    pub fn push_u16(&mut self, value: u16) -> u32 {
+        self.push(&value.to_le_bytes())
+    }
+
+    pub fn push_u32(&mut self, value: u32) -> u32 {
+        self.push(&value.to_le_bytes())
+    }
+
+    pub fn push_u64(&mut self, value: u64) -> u32 {
+        self.push(&value.to_le_bytes())
+    }
+
+    /// appends a NUL-terminated string and returns its RVA
+    pub fn push_cstring(&mut self, s: &str) -> u32 {
+        let rva = self.push(s.as_bytes());
+        self.push(&[0]);
+        rva
+    }
+
+    /// appends zeroes until the next byte is aligned to `alignment`
+    pub fn align(&mut self, alignment: usize) {
+        while !self.data.len().is_multiple_of(alignment) {
+            self.data.push(0);
+        }
+    }
+
+    pub fn patch_u32(&mut self, rva: u32, value: u32) {
+        let offset = (rva - self.virtual_address) as usize;
+        self.data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+struct Section {
+    name: String,
+    virtual_address: u32,
+    virtual_size: u32,
+    data: Vec<u8>,
+}
+
+/// creates a PE32 or PE32+ image with arbitrary sections and data directories
+pub struct PEBuilder {
+    is_64bit: bool,
+    machine: u16,
+    image_base: u64,
+    sections: Vec<Section>,
+    directories: [(u32, u32); 16],
+    header_data: Vec<u8>,
+}
+
+impl PEBuilder {
+    pub fn new(is_64bit: bool) -> Self {
+        Self {
+            is_64bit,
+            machine: if is_64bit { 0x8664 } else { 0x014c },
+            image_base: if is_64bit { 0x1_4000_0000 } else { 0x40_0000 },
+            sections: Vec::new(),
+            directories: [(0, 0); 16],
+            header_data: Vec::new(),
+        }
+    }
+
+    pub fn machine(mut self, machine: u16) -> Self {
+        self.machine = machine;
+        self
+    }
+
+    pub fn image_base(mut self, image_base: u64) -> Self {
+        self.image_base = image_base;
+        self
+    }
+
+    pub fn section(self, name: &str, section: SectionData) -> Self {
+        let virtual_size = section.data.len() as u32;
+        self.section_with_size(name, section, virtual_size)
+    }
+
+    /// adds a section whose virtual size differs from the size of its data
+    pub fn section_with_size(mut self, name: &str, section: SectionData, virtual_size: u32) -> Self {
+        self.sections.push(Section {
+            name: name.to_string(),
+            virtual_address: section.virtual_address,
+            virtual_size,
+            data: section.data,
+        });
+        self
+    }
+
+    pub fn directory(mut self, index: usize, rva: u32, size: u32) -> Self {
+        self.directories[index] = (rva, size);
+        self
+    }
+
+    /// stores `data` in the headers, directly after the section table.
+    /// Sections must be added before.
+    pub fn header_data(mut self, data: Vec<u8>) -> Self {
+        self.header_data = data;
+        self
+    }
+
+    /// RVA (and file offset) of the data which has been added using `header_data()`
+    pub fn header_data_offset(&self) -> u32 {
+        let optional_header_size = if self.is_64bit { 240 } else { 224 };
+        0x40 + 4 + 20 + optional_header_size + 40 * self.sections.len() as u32
+    }
+
+    pub fn size_of_headers(&self) -> u32 {
+        align(self.header_data_offset() + self.header_data.len() as u32, FILE_ALIGNMENT)
+    }
+
+    pub fn size_of_image(&self) -> u32 {
+        let end = self
+            .sections
+            .iter()
+            .map(|s| s.virtual_address + s.virtual_size)
+            .max()
+            .unwrap_or(0);
+        align(end.max(self.size_of_headers()), SECTION_ALIGNMENT)
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        let mut image = vec![0u8; self.size_of_headers() as usize];
+
+        // DOS header
+        image[0..2].copy_from_slice(b"MZ");
+        image[0x3c..0x40].copy_from_slice(&0x40u32.to_le_bytes());
+
+        let mut header = Vec::new();
+        header.extend_from_slice(b"PE\0\0");
+
+        // IMAGE_FILE_HEADER
+        let optional_header_size: u16 = if self.is_64bit { 240 } else { 224 };
+        header.extend_from_slice(&self.machine.to_le_bytes());
+        header.extend_from_slice(&(self.sections.len() as u16).to_le_bytes());
+        header.extend_from_slice(&[0; 12]);
+        header.extend_from_slice(&optional_header_size.to_le_bytes());
+        header.extend_from_slice(&0x2102u16.to_le_bytes());
+
+        // IMAGE_OPTIONAL_HEADER
+        let magic: u16 = if self.is_64bit { 0x20b } else { 0x10b };
+        header.extend_from_slice(&magic.to_le_bytes());
+        header.extend_from_slice(&[14, 0]);
+        header.extend_from_slice(&[0; 16]); // sizes of code/data, entry point
+        header.extend_from_slice(&0x1000u32.to_le_bytes()); // BaseOfCode
+        if self.is_64bit {
+            header.extend_from_slice(&self.image_base.to_le_bytes());
+        } else {
+            header.extend_from_slice(&0u32.to_le_bytes()); // BaseOfData
+            header.extend_from_slice(&(self.image_base as u32).to_le_bytes());
+        }
+        header.extend_from_slice(&SECTION_ALIGNMENT.to_le_bytes());
+        header.extend_from_slice(&FILE_ALIGNMENT.to_le_bytes());
+        header.extend_from_slice(&[6, 0, 0, 0, 0, 0, 0, 0, 6, 0, 0, 0]); // versions
+        header.extend_from_slice(&0u32.to_le_bytes()); // Win32VersionValue
+        header.extend_from_slice(&self.size_of_image().to_le_bytes());
+        header.extend_from_slice(&self.size_of_headers().to_le_bytes());
+        header.extend_from_slice(&0u32.to_le_bytes()); // CheckSum
+        header.extend_from_slice(&2u16.to_le_bytes()); // Subsystem
+        header.extend_from_slice(&0x140u16.to_le_bytes()); // DllCharacteristics
+        let stack_and_heap = if self.is_64bit { 32 } else { 16 };
+        header.extend_from_slice(&vec![0; stack_and_heap]);
+        header.extend_from_slice(&0u32.to_le_bytes()); // LoaderFlags
+        header.extend_from_slice(&16u32.to_le_bytes()); // NumberOfRvaAndSizes
+        for (rva, size) in self.directories.iter() {
+            header.extend_from_slice(&rva.to_le_bytes());
+            header.extend_from_slice(&size.to_le_bytes());
+        }
+
+        // section table
+        let mut raw_offset = self.size_of_headers();
+        let mut raw_data = Vec::new();
+        for section in self.sections.iter() {
+            let raw_size = align(section.data.len() as u32, FILE_ALIGNMENT);
+            let mut name = [0u8; 8];
+            name[..section.name.len()].copy_from_slice(section.name.as_bytes());
+            header.extend_from_slice(&name);
+            header.extend_from_slice(&section.virtual_size.to_le_bytes());
+            header.extend_from_slice(&section.virtual_address.to_le_bytes());
+            header.extend_from_slice(&raw_size.to_le_bytes());
+            header.extend_from_slice(&raw_offset.to_le_bytes());
+            header.extend_from_slice(&[0; 12]);
+            header.extend_from_slice(&0xc000_0040u32.to_le_bytes());
+
+            let mut data = section.data.clone();
+            data.resize(raw_size as usize, 0);
+            raw_data.extend(data);
+            raw_offset += raw_size;
+        }
+
+        header.extend_from_slice(&self.header_data);
+        image[0x40..0x40 + header.len()].copy_from_slice(&header);
+        image.extend(raw_data);
+        image
+    }
+}
+
+/// identifies a resource type or a resource name
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ResId {
+    Name(String),
+    Id(u16),
+}
+
+impl From<u16> for ResId {
+    fn from(id: u16) -> Self {
+        ResId::Id(id)
+    }
+}
+
+impl From<&str> for ResId {
+    fn from(name: &str) -> Self {
+        ResId::Name(name.to_string())
+    }
+}
+
+type ResourceTree<'a> = std::collections::BTreeMap<&'a ResId, std::collections::BTreeMap<&'a ResId, std::collections::BTreeMap<u16, &'a [u8]>>>;
+
+/// creates a resource section with a three-level tree (type, name, language)
+#[derive(Default)]
+pub struct ResourceBuilder {
+    entries: Vec<(ResId, ResId, u16, Vec<u8>)>,
+}
+
+impl ResourceBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add<T: Into<ResId>, N: Into<ResId>>(mut self, type_: T, name: N, lang: u16, data: Vec<u8>) -> Self {
+        self.entries.push((type_.into(), name.into(), lang, data));
+        self
+    }
+
+    /// creates the resource section, which will be mapped at `virtual_address`.
+    ///
+    /// The section contains all directories, then all data entries, then all names, then the data.
+    pub fn build(&self, virtual_address: u32) -> SectionData {
+        let mut tree = ResourceTree::new();
+        for (type_, name, lang, data) in self.entries.iter() {
+            tree.entry(type_).or_default().entry(name).or_default().insert(*lang, &data[..]);
+        }
+        let dir_size = |entries: usize| 16 + 8 * entries as u32;
+
+        let mut directories_size = dir_size(tree.len());
+        for names in tree.values() {
+            directories_size += dir_size(names.len());
+            directories_size += names.values().map(|langs| dir_size(langs.len())).sum::<u32>();
+        }
+        let data_entries_offset = directories_size;
+
+        let mut names: Vec<(String, u32)> = Vec::new();
+        let mut names_size = 0;
+        for id in tree.keys().chain(tree.values().flat_map(|n| n.keys())) {
+            if let ResId::Name(s) = id {
+                if !names.iter().any(|(n, _)| n == s) {
+                    names.push((s.clone(), data_entries_offset + 16 * self.entries.len() as u32 + names_size));
+                    names_size += 2 + 2 * s.encode_utf16().count() as u32;
+                }
+            }
+        }
+        let data_offset = (data_entries_offset + 16 * self.entries.len() as u32 + names_size + 7) & !7;
+
+        let name_offset = |s: &str| names.iter().find(|(n, _)| n == s).unwrap().1;
+        let push_dir = |section: &mut SectionData, entries: Vec<(&ResId, u32)>| {
+            let named = entries.iter().filter(|(id, _)| matches!(id, ResId::Name(_))).count();
+            section.push(&[0; 12]);
+            section.push_u16(named as u16);
+            section.push_u16((entries.len() - named) as u16);
+            for (id, offset) in entries {
+                match id {
+                    ResId::Name(s) => section.push_u32(0x8000_0000 | name_offset(s)),
+                    ResId::Id(id) => section.push_u32(*id as u32),
+                };
+                section.push_u32(offset);
+            }
+        };
+
+        let mut section = SectionData::new(virtual_address);
+
+        // root directory
+        let mut offset = dir_size(tree.len());
+        let mut entries = Vec::new();
+        for (type_, names) in tree.iter() {
+            entries.push((*type_, 0x8000_0000 | offset));
+            offset += dir_size(names.len());
+        }
+        push_dir(&mut section, entries);
+
+        // type directories
+        for names in tree.values() {
+            let mut entries = Vec::new();
+            for (name, langs) in names.iter() {
+                entries.push((*name, 0x8000_0000 | offset));
+                offset += dir_size(langs.len());
+            }
+            push_dir(&mut section, entries);
+        }
+
+        // language directories
+        let mut data_entries = Vec::new();
+        let mut data = Vec::new();
+        for langs in tree.values().flat_map(|n| n.values()) {
+            let ids: Vec<ResId> = langs.keys().map(|l| ResId::Id(*l)).collect();
+            let mut entries = Vec::new();
+            for (id, content) in ids.iter().zip(langs.values()) {
+                entries.push((id, data_entries_offset + 16 * data_entries.len() as u32));
+                data_entries.push((virtual_address + data_offset + data.len() as u32, content.len() as u32));
+                data.extend_from_slice(content);
+                data.resize(data.len().next_multiple_of(8), 0);
+            }
+            push_dir(&mut section, entries);
+        }
+
+        for (rva, size) in data_entries {
+            section.push_u32(rva);
+            section.push_u32(size);
+            section.push_u32(0);
+            section.push_u32(0);
+        }
+        for (name, _) in names.iter() {
+            section.push_u16(name.encode_utf16().count() as u16);
+            for c in name.encode_utf16() {
+                section.push_u16(c);
+            }
+        }
+        section.align(8);
+        section.push(&data);
+        section
+    }
+}
+
+/// creates a MESSAGE_TABLE resource with UTF-16 encoded messages.
+///
+/// Every block consists of the lowest id and the messages
+pub fn message_table(blocks: &[(u32, &[&str])]) -> Vec<u8> {
+    let mut header = Vec::new();
+    let mut entries = Vec::new();
+    let entries_offset = 4 + 12 * blocks.len();
+    header.extend_from_slice(&(blocks.len() as u32).to_le_bytes());
+    for (low_id, messages) in blocks {
+        header.extend_from_slice(&low_id.to_le_bytes());
+        header.extend_from_slice(&(low_id + messages.len() as u32 - 1).to_le_bytes());
+        header.extend_from_slice(&((entries_offset + entries.len()) as u32).to_le_bytes());
+        for message in messages.iter() {
+            let mut text: Vec<u8> = message.encode_utf16().flat_map(|c| c.to_le_bytes()).collect();
+            text.extend_from_slice(&[0, 0]);
+            text.resize(text.len().next_multiple_of(4), 0);
+            entries.extend_from_slice(&((text.len() + 4) as u16).to_le_bytes());
+            entries.extend_from_slice(&1u16.to_le_bytes());
+            entries.extend(text);
+        }
+    }
+    header.extend(entries);
+    header
+}
+
+/// encodes little endian resource structures like dialog and menu templates
+#[derive(Default)]
+pub struct TemplateBuilder(pub Vec<u8>);
+
+impl TemplateBuilder {
+    pub fn u8(mut self, value: u8) -> Self {
+        self.0.push(value);
+        self
+    }
+    pub fn u16(mut self, value: u16) -> Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+    pub fn u32(mut self, value: u32) -> Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+    pub fn bytes(mut self, bytes: &[u8]) -> Self {
+        self.0.extend_from_slice(bytes);
+        self
+    }
+    pub fn rect(self, x: i16, y: i16, cx: i16, cy: i16) -> Self {
+        self.u16(x as u16).u16(y as u16).u16(cx as u16).u16(cy as u16)
+    }
+    pub fn string(mut self, s: &str) -> Self {
+        for c in s.encode_utf16().chain(Some(0)) {
+            self.0.extend_from_slice(&c.to_le_bytes());
+        }
+        self
+    }
+    pub fn ordinal(self, ordinal: u16) -> Self {
+        self.u16(0xffff).u16(ordinal)
+    }
+    pub fn align(mut self) -> Self {
+        self.0.resize(self.0.len().next_multiple_of(4), 0);
+        self
+    }
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+
+const RT_BITMAP: u16 = 2;
+
+/// creates a BITMAPINFOHEADER-style header of `size` bytes
+fn info_header(size: u32, bit_count: u16, compression: u32, colors_used: u32) -> Vec<u8> {
+    let mut header = Vec::new();
+    header.extend_from_slice(&size.to_le_bytes());
+    header.extend_from_slice(&2i32.to_le_bytes());
+    header.extend_from_slice(&2i32.to_le_bytes());
+    header.extend_from_slice(&1u16.to_le_bytes());
+    header.extend_from_slice(&bit_count.to_le_bytes());
+    header.extend_from_slice(&compression.to_le_bytes());
+    header.extend_from_slice(&[0; 12]);
+    header.extend_from_slice(&colors_used.to_le_bytes());
+    header.extend_from_slice(&0u32.to_le_bytes());
+    header.resize(size as usize, 0);
+    header
+}
+
+fn pixel_offset(bmp: &[u8]) -> usize {
+    u32::from_le_bytes([bmp[10], bmp[11], bmp[12], bmp[13]]) as usize
+}
+
+#[test]
+fn header_variants() -> Result<()> {
+    // BITMAPCOREHEADER with a palette of 16 RGBTRIPLEs
+    let mut dib = vec![12, 0, 0, 0, 2, 0, 2, 0, 1, 0, 4, 0];
+    dib.resize(12 + 16 * 3 + 8, 0);
+    let bmp = dib_to_bmp(&dib)?;
+    assert_eq!(&bmp[0..2], b"BM");
+    assert_eq!(u32::from_le_bytes([bmp[2], bmp[3], bmp[4], bmp[5]]) as usize, bmp.len());
+    assert_eq!(pixel_offset(&bmp), 14 + 12 + 48);
+    assert_eq!(&bmp[14..], &dib[..]);
+
+    // BITMAPINFOHEADER with a palette of 256 RGBQUADs
+    let mut dib = info_header(40, 8, 0, 0);
+    dib.resize(40 + 256 * 4 + 8, 0);
+    assert_eq!(pixel_offset(&dib_to_bmp(&dib)?), 14 + 40 + 1024);
+
+    // BITMAPINFOHEADER with a reduced palette
+    let mut dib = info_header(40, 8, 0, 3);
+    dib.resize(40 + 3 * 4 + 8, 0);
+    assert_eq!(pixel_offset(&dib_to_bmp(&dib)?), 14 + 40 + 12);
+
+    // BITMAPINFOHEADER with BI_BITFIELDS masks
+    let mut dib = info_header(40, 16, 3, 0);
+    dib.resize(40 + 12 + 8, 0);
+    assert_eq!(pixel_offset(&dib_to_bmp(&dib)?), 14 + 40 + 12);
+
+    // BITMAPV4HEADER and BITMAPV5HEADER contain their masks
+    for size in [108, 124] {
+        let mut dib = info_header(size, 32, 3, 0);
+        dib.resize(size as usize + 16, 0);
+        assert_eq!(pixel_offset(&dib_to_bmp(&dib)?), 14 + size as usize);
+    }
+
+    // BI_JPEG and BI_PNG bitmaps have no bit count and no palette
+    for compression in [4, 5] {
+        let mut dib = info_header(40, 0, compression, 0);
+        dib.resize(40 + 8, 0);
+        assert_eq!(pixel_offset(&dib_to_bmp(&dib)?), 14 + 40);
+    }
+    Ok(())
+}
+
+#[test]
+fn invalid_bitmaps() {
+    let mut dib = info_header(41, 24, 0, 0);
+    dib.resize(64, 0);
+    assert!(matches!(dib_to_bmp(&dib), Err(Error::InvalidData { .. })));
+
+    let dib = info_header(40, 8, 0, 0);
+    assert!(matches!(dib_to_bmp(&dib), Err(Error::Truncated { .. })));
+
+    let dib = info_header(40, 8, 0, u32::MAX);
+    assert!(dib_to_bmp(&dib).is_err());
+    assert!(dib_to_bmp(&[40, 0]).is_err());
+}
+
+#[test]
+fn bitmap_resources() -> Result<()> {
+    let mut dib = info_header(40, 24, 0, 0);
+    dib.extend_from_slice(&[0x11; 16]);
+    let resources = ResourceBuilder::new()
+        .add(RT_BITMAP, "LOGO", 0x0409, dib.clone())
+        .build(0x1000);
+    let size = resources.data.len() as u32;
+    let pefile = PEFile::from_data(
+        PEBuilder::new(true)
+            .directory(IMAGE_DIRECTORY_ENTRY_RESOURCE, 0x1000, size)
+            .section(".rsrc", resources)
+            .build(),
+    )?;
+
+    let bitmaps = pefile.bitmaps()?;
+    assert_eq!(bitmaps.len(), 1);
+    assert_eq!(bitmaps[0].name, EntryIdentifier::Name("LOGO".to_string()));
+    assert_eq!(bitmaps[0].lang_id, Some(0x0409));
+    let bmp = bitmaps[0].to_file(&pefile)?;
+    assert_eq!(bmp.len(), 14 + dib.len());
+    assert_eq!(pixel_offset(&bmp), 14 + 40);
+    assert_eq!(&bmp[pixel_offset(&bmp)..], &[0x11; 16]);
+    Ok(())
+}
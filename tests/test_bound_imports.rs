@@ -0,0 +1,57 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+
+/// creates a bound import directory, which references KERNEL32.dll
+/// (with a forwarder to NTDLL.DLL) and USER32.dll
+fn bound_import_directory() -> Vec<u8> {
+    let mut directory = Vec::new();
+    let entries: [(u32, u16, u16); 4] = [
+        (0x4ce7_9b8d, 32, 1), // KERNEL32.dll
+        (0x4ce7_9c11, 45, 0), // forwarder to NTDLL.DLL
+        (0x4ce7_9d22, 55, 0), // USER32.dll
+        (0, 0, 0),
+    ];
+    for (timestamp, name, count) in entries.iter() {
+        directory.extend_from_slice(&timestamp.to_le_bytes());
+        directory.extend_from_slice(&name.to_le_bytes());
+        directory.extend_from_slice(&count.to_le_bytes());
+    }
+    directory.extend_from_slice(b"KERNEL32.dll\0NTDLL.DLL\0USER32.dll\0");
+    directory
+}
+
+#[test]
+fn bound_imports() -> Result<()> {
+    let directory = bound_import_directory();
+    let builder = PEBuilder::new(false).section(".text", SectionData::new(0x1000));
+    let offset = builder.header_data_offset();
+    let image = builder
+        .directory(IMAGE_DIRECTORY_ENTRY_BOUND_IMPORT, offset, directory.len() as u32)
+        .header_data(directory)
+        .build();
+
+    let pefile = PEFile::from_data(image)?;
+    let dlls = pefile.bound_imports()?;
+    assert_eq!(dlls.len(), 2);
+
+    assert_eq!(dlls[0].name, "KERNEL32.dll");
+    assert_eq!(dlls[0].descriptor.TimeDateStamp, 0x4ce7_9b8d);
+    assert_eq!(dlls[0].forwarders.len(), 1);
+    assert_eq!(dlls[0].forwarders[0].name, "NTDLL.DLL");
+    assert_eq!(dlls[0].forwarders[0].forwarder_ref.TimeDateStamp, 0x4ce7_9c11);
+
+    assert_eq!(dlls[1].name, "USER32.dll");
+    assert_eq!(dlls[1].descriptor.TimeDateStamp, 0x4ce7_9d22);
+    assert!(dlls[1].forwarders.is_empty());
+    Ok(())
+}
+
+#[test]
+fn no_bound_imports() -> Result<()> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let pefile = PEFile::new(format!("{}/samples/msaudite.dll", manifest_dir).into())?;
+    assert!(pefile.bound_imports()?.is_empty());
+    Ok(())
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+
+const IMAGE_BASE: u64 = 0x1000_0000;
+
+/// creates an image which delay-loads `Function` and ordinal 3 from `DELAYED.dll`.
+/// If `rva_based` is false, the descriptor and the name table contain virtual addresses.
+fn image_with_delay_imports(rva_based: bool) -> Vec<u8> {
+    let mut didat = SectionData::new(0x3000);
+    let base = if rva_based { 0 } else { IMAGE_BASE as u32 };
+
+    let descriptor = didat.push(&[0; 64]);
+    let dll_name = didat.push_cstring("DELAYED.dll");
+    didat.align(2);
+    let function = didat.push_u16(7);
+    didat.push_cstring("Function");
+    didat.align(4);
+    let int = didat.push_u32(base + function);
+    didat.push_u32(0x8000_0003);
+    didat.push_u32(0);
+    let iat = didat.push(&[0; 12]);
+
+    let attributes = if rva_based { 1 } else { 0 };
+    for (field, value) in [(0, attributes), (4, base + dll_name), (12, base + iat), (16, base + int)].iter() {
+        didat.patch_u32(descriptor + field, *value);
+    }
+
+    PEBuilder::new(false)
+        .image_base(IMAGE_BASE)
+        .directory(IMAGE_DIRECTORY_ENTRY_DELAY_IMPORT, descriptor, 64)
+        .section(".didat", didat)
+        .build()
+}
+
+fn check_delay_imports(rva_based: bool) -> Result<()> {
+    let pefile = PEFile::from_data(image_with_delay_imports(rva_based))?;
+    assert_eq!(pefile.image_base(), IMAGE_BASE);
+
+    let dlls = pefile.delay_imports()?.collect::<Result<Vec<DelayImportedDll>>>()?;
+    assert_eq!(dlls.len(), 1);
+    assert_eq!(dlls[0].name, "DELAYED.dll");
+    assert_eq!(dlls[0].descriptor.is_rva_based(), rva_based);
+
+    let functions = &dlls[0].functions;
+    assert_eq!(functions.len(), 2);
+    assert_eq!(functions[0].symbol, ImportedSymbol::ByName { hint: 7, name: "Function".to_string() });
+    assert_eq!(functions[1].symbol, ImportedSymbol::ByOrdinal(3));
+    assert_eq!(functions[0].iat_rva + 4, functions[1].iat_rva);
+
+    // there is no regular import directory
+    assert_eq!(pefile.imports()?.count(), 0);
+    Ok(())
+}
+
+#[test]
+fn rva_based_delay_imports() -> Result<()> {
+    check_delay_imports(true)
+}
+
+#[test]
+fn va_based_delay_imports() -> Result<()> {
+    check_delay_imports(false)
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+
+const RT_DIALOG: u16 = 5;
+const DS_SETFONT: u32 = 0x40;
+
+fn image_with_dialog(template: Vec<u8>) -> Result<PEFile> {
+    let resources = ResourceBuilder::new()
+        .add(RT_DIALOG, 100u16, 0x0409, template)
+        .build(0x1000);
+    let size = resources.data.len() as u32;
+    PEFile::from_data(
+        PEBuilder::new(false)
+            .directory(IMAGE_DIRECTORY_ENTRY_RESOURCE, 0x1000, size)
+            .section(".rsrc", resources)
+            .build(),
+    )
+}
+
+#[test]
+fn classic_template() -> Result<()> {
+    let template = TemplateBuilder::default()
+        .u32(0x80c80000 | DS_SETFONT).u32(0).u16(2).rect(0, 0, 200, 100)
+        .u16(0).string("MyDialogClass").string("About")
+        .u16(8).string("MS Shell Dlg")
+        .align()
+        .u32(0x50010001).u32(0).rect(140, 80, 50, 14).u16(1)
+        .ordinal(0x80).string("OK").u16(0)
+        .align()
+        .u32(0x50000000).u32(0x20).rect(10, 10, 120, -1).u16(0xffff)
+        .string("SysLink").ordinal(200).u16(4).u16(0xabcd);
+    let pefile = image_with_dialog(template.0)?;
+    let dialogs = pefile.dialogs()?;
+    assert_eq!(dialogs.len(), 1);
+
+    let dialog = &dialogs[0];
+    assert!(!dialog.extended);
+    assert_eq!(dialog.name, EntryIdentifier::Id(100));
+    assert_eq!(dialog.lang_id, Some(0x0409));
+    assert_eq!((dialog.cx, dialog.cy), (200, 100));
+    assert_eq!(dialog.menu, NameOrOrdinal::None);
+    assert_eq!(dialog.class, NameOrOrdinal::Name("MyDialogClass".to_string()));
+    assert_eq!(dialog.caption, "About");
+    let font = dialog.font.as_ref().unwrap();
+    assert_eq!((font.point_size, &font.typeface[..]), (8, "MS Shell Dlg"));
+
+    assert_eq!(dialog.controls.len(), 2);
+    let ok = &dialog.controls[0];
+    assert_eq!(ok.id, 1);
+    assert_eq!(ok.class_name().as_deref(), Some("Button"));
+    assert_eq!(ok.text, NameOrOrdinal::Name("OK".to_string()));
+    assert_eq!((ok.x, ok.y, ok.cx, ok.cy), (140, 80, 50, 14));
+    assert!(ok.creation_data.is_empty());
+
+    let link = &dialog.controls[1];
+    assert_eq!(link.id, 0xffff);
+    assert_eq!(link.ex_style, 0x20);
+    assert_eq!(link.cy, -1);
+    assert_eq!(link.class_name().as_deref(), Some("SysLink"));
+    assert_eq!(link.text, NameOrOrdinal::Ordinal(200));
+    assert_eq!(link.creation_data, vec![0xcd, 0xab]);
+    Ok(())
+}
+
+#[test]
+fn extended_template() -> Result<()> {
+    let template = TemplateBuilder::default()
+        .u16(1).u16(0xffff).u32(77).u32(0x100).u32(0x80c80000 | DS_SETFONT).u16(1).rect(5, 6, 300, 150)
+        .ordinal(300).ordinal(0x8002).string("Einstellungen")
+        .u16(9).u16(700).u8(1).u8(1).string("Segoe UI")
+        .align()
+        .u32(55).u32(0).u32(0x50010000).rect(1, 2, 3, 4).u32(0x12345)
+        .ordinal(0x85).u16(0).u16(3).u8(1).u8(2).u8(3);
+    let pefile = image_with_dialog(template.0)?;
+    let dialog = &pefile.dialogs()?[0];
+
+    assert!(dialog.extended);
+    assert_eq!(dialog.help_id, 77);
+    assert_eq!(dialog.ex_style, 0x100);
+    assert_eq!((dialog.x, dialog.y), (5, 6));
+    assert_eq!(dialog.menu, NameOrOrdinal::Ordinal(300));
+    assert_eq!(dialog.class, NameOrOrdinal::Ordinal(0x8002));
+    assert_eq!(dialog.caption, "Einstellungen");
+    assert_eq!(
+        dialog.font,
+        Some(DialogFont { point_size: 9, weight: 700, italic: true, charset: 1, typeface: "Segoe UI".to_string() })
+    );
+
+    let combo = &dialog.controls[0];
+    assert_eq!(combo.id, 0x12345);
+    assert_eq!(combo.help_id, 55);
+    assert_eq!(combo.class_name().as_deref(), Some("ComboBox"));
+    assert_eq!(combo.text, NameOrOrdinal::None);
+    assert_eq!(combo.creation_data, vec![1, 2, 3]);
+    Ok(())
+}
+
+#[test]
+fn truncated_template() -> Result<()> {
+    // the second control is missing
+    let template = TemplateBuilder::default()
+        .u32(0x80c80000).u32(0).u16(2).rect(0, 0, 200, 100)
+        .u16(0).u16(0).string("Broken")
+        .align()
+        .u32(0x50010001).u32(0).rect(140, 80, 50, 14).u16(1)
+        .ordinal(0x80).string("OK").u16(0);
+    let pefile = image_with_dialog(template.0)?;
+    assert!(matches!(pefile.dialogs(), Err(Error::Truncated { .. })));
+
+    // the caption is not terminated
+    let mut template = TemplateBuilder::default()
+        .u32(0x80c80000).u32(0).u16(0).rect(0, 0, 200, 100)
+        .u16(0).u16(0).string("Unterminated").0;
+    template.truncate(template.len() - 2);
+    let pefile = image_with_dialog(template)?;
+    assert!(matches!(pefile.dialogs(), Err(Error::InvalidData { .. })));
+    Ok(())
+}
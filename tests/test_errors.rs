@@ -0,0 +1,148 @@
+mod common;
+
+use std::path::PathBuf;
+use byteorder::{ByteOrder, LittleEndian};
+use common::*;
+use libpefile::*;
+
+fn sample_data() -> Vec<u8> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    std::fs::read(PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir))).unwrap()
+}
+
+fn nt_offset(data: &[u8]) -> usize {
+    LittleEndian::read_u32(&data[0x3c..]) as usize
+}
+
+#[test]
+fn bad_dos_magic() {
+    let mut data = sample_data();
+    data[0] = b'X';
+    assert!(matches!(PEFile::from_data(data), Err(Error::BadDosMagic(0x5a58))));
+}
+
+#[test]
+fn bad_nt_signature() {
+    let mut data = sample_data();
+    let offset = nt_offset(&data);
+    data[offset + 1] = b'X';
+    assert!(matches!(PEFile::from_data(data), Err(Error::BadNtSignature([b'P', b'X', 0, 0]))));
+}
+
+#[test]
+fn unknown_optional_header_magic() {
+    let mut data = sample_data();
+    let offset = nt_offset(&data) + 24;
+    LittleEndian::write_u16(&mut data[offset..], 0x1234);
+    assert!(matches!(PEFile::from_data(data), Err(Error::UnknownOptionalHeaderMagic(0x1234))));
+}
+
+#[test]
+fn truncated_dos_header() {
+    let data = &sample_data()[..0x20];
+    assert!(matches!(PEFile::from_data(data), Err(Error::Truncated { offset: 0, needed: 64 })));
+}
+
+#[test]
+fn resource_loop() {
+    let mut data = sample_data();
+
+    // let the first entry of the root resource directory point to the root directory
+    let resources = PEFile::from_data(&data[..]).unwrap().get_raw_address(0x2000).unwrap();
+    LittleEndian::write_u32(&mut data[resources + 20..], 0x80000000);
+
+    let pefile = PEFile::from_data(data).unwrap();
+    assert!(matches!(pefile.messages_iter(), Err(Error::ResourceLoop(0))));
+}
+
+/// creates a chain of resource directories, where every entry of a directory points to
+/// the next directory. The entries of the last directory point to a data entry.
+fn directory_chain(directories: usize, entries: u16) -> Result<PEFile> {
+    let mut section = SectionData::new(0x1000);
+    let directory_size = 16 + 8 * entries as u32;
+    let data_entry = directories as u32 * directory_size;
+    for idx in 0..directories as u32 {
+        let next = if idx as usize + 1 == directories {
+            data_entry
+        } else {
+            0x80000000 | ((idx + 1) * directory_size)
+        };
+        section.push(&[0; 12]);
+        section.push_u16(0);
+        section.push_u16(entries);
+        for id in 0..entries as u32 {
+            section.push_u32(id + 1);
+            section.push_u32(next);
+        }
+    }
+    section.push_u32(0x1000);
+    section.push_u32(4);
+    section.push_u32(0);
+    section.push_u32(0);
+
+    let size = section.data.len() as u32;
+    PEFile::from_data(
+        PEBuilder::new(true)
+            .directory(IMAGE_DIRECTORY_ENTRY_RESOURCE, 0x1000, size)
+            .section(".rsrc", section)
+            .build(),
+    )
+}
+
+#[derive(Default)]
+struct CountingVisitor {
+    directories: usize,
+    entries: usize,
+}
+
+impl ResourceDirectoryVisitor for CountingVisitor {
+    fn enter_resource_directory(&mut self, _: &IMAGE_RESOURCE_DIRECTORY, _: &EntryIdentifier) -> Result<()> {
+        self.directories += 1;
+        Ok(())
+    }
+    fn leave_resource_directory(&mut self, _: &IMAGE_RESOURCE_DIRECTORY, _: &EntryIdentifier) -> Result<()> {
+        Ok(())
+    }
+    fn visit_resource_data_entry(&mut self, _: &IMAGE_RESOURCE_DATA_ENTRY, _: &EntryIdentifier) -> Result<()> {
+        self.entries += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn deeply_nested_resources() -> Result<()> {
+    // this used to overflow the stack
+    let pefile = directory_chain(40_000, 1)?;
+    assert!(matches!(pefile.resources_tree(), Err(Error::InvalidResourceLayout(_))));
+
+    // the usual three levels of type, name and language are fine
+    let pefile = directory_chain(3, 1)?;
+    let tree = pefile.resources_tree()?;
+    assert_eq!(tree.iter().count(), 1);
+    Ok(())
+}
+
+#[test]
+fn shared_resource_directories() -> Result<()> {
+    // directories which are referenced several times are visited once per reference
+    let pefile = directory_chain(3, 2)?;
+    let mut visitor = CountingVisitor::default();
+    pefile.visit_resource_tree(&mut visitor)?;
+    assert_eq!(visitor.directories, 1 + 2 + 4);
+    assert_eq!(visitor.entries, 8);
+    assert_eq!(pefile.resources_tree()?.iter().count(), 8);
+
+    // walking every path through this tree would take 1000^6 steps
+    let pefile = directory_chain(7, 1000)?;
+    let mut visitor = CountingVisitor::default();
+    assert!(matches!(pefile.visit_resource_tree(&mut visitor), Err(Error::InvalidResourceLayout(_))));
+    Ok(())
+}
+
+#[test]
+fn convert_into_io_error() {
+    let mut data = sample_data();
+    data[0] = b'X';
+    let why: std::io::Error = PEFile::from_data(data).err().unwrap().into();
+    assert_eq!(why.kind(), std::io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+
+/// creates an image which exports
+///  - `Alpha` with ordinal 5,
+///  - an unnamed function with ordinal 7 and
+///  - `HeapAlloc` with ordinal 8, which is forwarded to `NTDLL.RtlAllocateHeap`
+fn image_with_exports() -> Vec<u8> {
+    let mut edata = SectionData::new(0x2000);
+    let directory = edata.push(&[0; 40]);
+    let dll_name = edata.push_cstring("TEST.dll");
+    let alpha = edata.push_cstring("Alpha");
+    let heap_alloc = edata.push_cstring("HeapAlloc");
+    let forwarder = edata.push_cstring("NTDLL.RtlAllocateHeap");
+    edata.align(4);
+
+    let functions = edata.rva();
+    for rva in [0x1000, 0, 0x1010, forwarder].iter() {
+        edata.push_u32(*rva);
+    }
+    let names = edata.rva();
+    edata.push_u32(alpha);
+    edata.push_u32(heap_alloc);
+    let ordinals = edata.rva();
+    edata.push_u16(0);
+    edata.push_u16(3);
+    let size = edata.rva() - directory;
+
+    for (field, value) in [(12, dll_name), (16, 5), (20, 4), (24, 2), (28, functions), (32, names), (36, ordinals)].iter() {
+        edata.patch_u32(directory + field, *value);
+    }
+
+    PEBuilder::new(false)
+        .directory(IMAGE_DIRECTORY_ENTRY_EXPORT, directory, size)
+        .section(".edata", edata)
+        .build()
+}
+
+#[test]
+fn exports() -> Result<()> {
+    let pefile = PEFile::from_data(image_with_exports())?;
+    let exports = pefile.exports()?.unwrap();
+    assert_eq!(exports.name, "TEST.dll");
+    assert_eq!(exports.directory.Base, 5);
+    assert_eq!(exports.functions.len(), 3);
+
+    let alpha = &exports.functions[0];
+    assert_eq!((alpha.ordinal, alpha.name(), alpha.rva), (5, Some("Alpha"), 0x1000));
+    assert!(alpha.forwarder.is_none());
+
+    let unnamed = &exports.functions[1];
+    assert_eq!((unnamed.ordinal, unnamed.name(), unnamed.rva), (7, None, 0x1010));
+
+    let heap_alloc = &exports.functions[2];
+    assert_eq!((heap_alloc.ordinal, heap_alloc.name()), (8, Some("HeapAlloc")));
+    assert_eq!(heap_alloc.forwarder.as_deref(), Some("NTDLL.RtlAllocateHeap"));
+    Ok(())
+}
+
+#[test]
+fn aliases() -> Result<()> {
+    let mut edata = SectionData::new(0x2000);
+    let directory = edata.push(&[0; 40]);
+    let dll_name = edata.push_cstring("TEST.dll");
+    let alpha = edata.push_cstring("Alpha");
+    let beta = edata.push_cstring("Beta");
+    edata.align(4);
+
+    // both names refer to the only function
+    let functions = edata.push_u32(0x1000);
+    let names = edata.push_u32(alpha);
+    edata.push_u32(beta);
+    let ordinals = edata.push_u16(0);
+    edata.push_u16(0);
+    let size = edata.rva() - directory;
+    for (field, value) in [(12, dll_name), (16, 1), (20, 1), (24, 2), (28, functions), (32, names), (36, ordinals)].iter() {
+        edata.patch_u32(directory + field, *value);
+    }
+
+    let pefile = PEFile::from_data(
+        PEBuilder::new(false)
+            .directory(IMAGE_DIRECTORY_ENTRY_EXPORT, directory, size)
+            .section(".edata", edata)
+            .build(),
+    )?;
+    let exports = pefile.exports()?.unwrap();
+    assert_eq!(exports.functions.len(), 1);
+    assert_eq!(exports.functions[0].names, vec!["Alpha", "Beta"]);
+    assert_eq!(exports.functions[0].name(), Some("Alpha"));
+    Ok(())
+}
+
+#[test]
+fn no_exports() -> Result<()> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let pefile = PEFile::new(format!("{}/samples/msaudite.dll", manifest_dir).into())?;
+    assert!(pefile.exports()?.is_none());
+    Ok(())
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+
+const RT_FONTDIR: u16 = 7;
+const RT_FONT: u16 = 8;
+
+/// creates a FONTDIRENTRY with its ordinal, device name and face name
+fn font_dir_entry(ordinal: u16, points: u16, weight: u16, italic: bool, device: &str, face: &str) -> Vec<u8> {
+    let mut copyright = b"Copyright \xa9 Example".to_vec();
+    copyright.resize(60, 0);
+    TemplateBuilder::default()
+        .u16(ordinal)
+        .u16(0x0300).u32(0x1234).bytes(&copyright)
+        .u16(0).u16(points).u16(96).u16(96).u16(12).u16(2).u16(0)
+        .u8(italic as u8).u8(0).u8(0).u16(weight).u8(0)
+        .u16(0).u16(16).u8(0x31).u16(7).u16(14)
+        .u8(0x20).u8(0xff).u8(0x80).u8(0).u16(0)
+        .u32(0).u32(0x200).u32(0)
+        .bytes(device.as_bytes()).u8(0).bytes(face.as_bytes()).u8(0)
+        .0
+}
+
+fn image_with(resources: ResourceBuilder) -> Result<PEFile> {
+    let resources = resources.build(0x1000);
+    let size = resources.data.len() as u32;
+    PEFile::from_data(
+        PEBuilder::new(false)
+            .directory(IMAGE_DIRECTORY_ENTRY_RESOURCE, 0x1000, size)
+            .section(".rsrc", resources)
+            .build(),
+    )
+}
+
+#[test]
+fn font_directory() -> Result<()> {
+    let mut fontdir = 2u16.to_le_bytes().to_vec();
+    fontdir.extend(font_dir_entry(1, 10, 400, false, "", "MS Sans Serif"));
+    fontdir.extend(font_dir_entry(2, 12, 700, true, "PRINTER", "Courier"));
+    let pefile = image_with(ResourceBuilder::new().add(RT_FONTDIR, "FONTDIR", 0x0409, fontdir))?;
+
+    let entries = pefile.font_directory()?.unwrap();
+    assert_eq!(entries.len(), 2);
+    let first = &entries[0];
+    assert_eq!((first.ordinal, first.version, first.size), (1, 0x0300, 0x1234));
+    assert_eq!(first.copyright, "Copyright © Example");
+    assert_eq!((first.points, first.vert_res, first.ascent, first.internal_leading), (10, 96, 12, 2));
+    assert_eq!((first.weight, first.italic), (400, false));
+    assert_eq!((first.pix_height, first.pitch_and_family, first.avg_width, first.max_width), (16, 0x31, 7, 14));
+    assert_eq!((first.first_char, first.last_char, first.default_char), (0x20, 0xff, 0x80));
+    assert_eq!(first.device_name, "");
+    assert_eq!(first.face_name, "MS Sans Serif");
+
+    let second = &entries[1];
+    assert_eq!((second.ordinal, second.weight, second.italic), (2, 700, true));
+    assert_eq!(second.device_name, "PRINTER");
+    assert_eq!(second.face_name, "Courier");
+    Ok(())
+}
+
+#[test]
+fn truncated_font_directory() -> Result<()> {
+    let mut fontdir = 2u16.to_le_bytes().to_vec();
+    fontdir.extend(font_dir_entry(1, 10, 400, false, "", "MS Sans Serif"));
+    let pefile = image_with(ResourceBuilder::new().add(RT_FONTDIR, "FONTDIR", 0, fontdir))?;
+    assert!(matches!(pefile.font_directory(), Err(Error::Truncated { .. })));
+
+    let pefile = PEFile::from_data(PEBuilder::new(false).build())?;
+    assert!(pefile.font_directory()?.is_none());
+    assert!(pefile.fonts()?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn font_formats() -> Result<()> {
+    let mut fnt = TemplateBuilder::default().u16(0x0200).u32(16).0;
+    fnt.resize(16, 0);
+    let pefile = image_with(
+        ResourceBuilder::new()
+            .add(RT_FONT, 1u16, 0, fnt.clone())
+            .add(RT_FONT, 2u16, 0, b"\x00\x01\x00\x00\x00\x0aglyf".to_vec())
+            .add(RT_FONT, 3u16, 0, b"OTTO\x00\x0aCFF ".to_vec())
+            .add(RT_FONT, 4u16, 0, b"ttcf\x00\x02\x00\x00".to_vec())
+            .add(RT_FONT, 5u16, 0, b"MZ\x90\x00\x03\x00".to_vec()),
+    )?;
+    let fonts = pefile.fonts()?;
+    let formats: Vec<_> = fonts.iter().map(|f| f.format).collect();
+    assert_eq!(
+        formats,
+        vec![
+            FontFormat::Fnt,
+            FontFormat::TrueType,
+            FontFormat::OpenType,
+            FontFormat::TrueTypeCollection,
+            FontFormat::Unknown
+        ]
+    );
+    assert_eq!(fonts[0].name, EntryIdentifier::Id(1));
+    assert_eq!(fonts[0].bytes(&pefile)?, &fnt[..]);
+    assert_eq!(fonts[1].format.extension(), "ttf");
+
+    // the size of an FNT file must not exceed the resource
+    let mut fnt = TemplateBuilder::default().u16(0x0300).u32(1000).0;
+    fnt.resize(16, 0);
+    assert_eq!(FontFormat::detect(&fnt), FontFormat::Unknown);
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use libpefile::*;
+use std::path::PathBuf;
+
+fn format(text: &str, inserts: &[Insert]) -> String {
+    Message::new(1, 0x0409, text.to_string()).format(inserts)
+}
+
+#[test]
+fn escape_sequences() {
+    assert_eq!(format("a%nb%tc%rd%be", &[]), "a\r\nb\tc\rd e");
+    assert_eq!(format("100%% done%. %!", &[]), "100% done. !");
+    assert_eq!(format("no newline%0\r\n", &[]), "no newline");
+    assert_eq!(format("terminated\r\n\0\0garbage", &[]), "terminated\r\n");
+}
+
+#[test]
+fn inserts() {
+    let inserts: Vec<Insert> = (1..=12).map(|i| Insert::from(format!("v{}", i))).collect();
+    assert_eq!(format("%1 %2!s! %12 %10", &inserts), "v1 v2 v12 v10");
+    assert_eq!(format("%1%100", &inserts), "v1v100");
+
+    // missing inserts are kept
+    assert_eq!(format("%1 %3 %2!d!", &["x".into()]), "x %3 %2!d!");
+}
+
+#[test]
+fn printf_specs() {
+    assert_eq!(format("[%1!5s!][%1!-5s!][%1!.2s!]", &["abc".into()]), "[  abc][abc  ][ab]");
+    assert_eq!(format("%1!d! %1!+d! %1!05d! %1!-5d!|", &[42.into()]), "42 +42 00042 42   |");
+    assert_eq!(format("%1!d! %1!.4d! %1!6.4d!", &[(-42).into()]), "-42 -0042  -0042");
+    assert_eq!(format("%1!x! %1!#X! %1!08lx! %1!o! %1!#o!", &[255u32.into()]), "ff 0XFF 000000ff 377 0377");
+    assert_eq!(format("%1!I64u! %1!lu!", &[Insert::Unsigned(u64::MAX)]), "18446744073709551615 4294967295");
+    assert_eq!(format("%1!c!%2!c!", &["xyz".into(), 65u32.into()]), "xA");
+
+    // string inserts are converted if the format requires a number
+    assert_eq!(format("%1!04x!", &["26".into()]), "001a");
+
+    // width and precision are taken from the following inserts
+    assert_eq!(format("[%1!*.*s!]", &["abcdef".into(), 6u32.into(), 3u32.into()]), "[   abc]");
+}
+
+#[test]
+fn integer_sizes() {
+    // without a 64 bit prefix, integers are truncated to 32 bit like FormatMessage does it
+    let inserts = [Insert::Signed(-1)];
+    assert_eq!(format("%1!x! %1!X! %1!u! %1!o!", &inserts), "ffffffff FFFFFFFF 4294967295 37777777777");
+    assert_eq!(format("%1!I64x! %1!llX!", &inserts), "ffffffffffffffff FFFFFFFFFFFFFFFF");
+    assert_eq!(format("%1!I64u!", &inserts), "18446744073709551615");
+    assert_eq!(format("%1!d! %1!I64d!", &[Insert::Unsigned(0xffff_ffff)]), "-1 4294967295");
+}
+
+#[test]
+fn format_sample_message() -> Result<()> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let pefile = PEFile::new(PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir)))?;
+
+    let msg = pefile.message(516, 0x0409)?.unwrap();
+    assert_eq!(
+        msg.format(&["17".into()]),
+        "Internal resources allocated for the queuing of audit messages have been exhausted,\r\n\
+         leading to the loss of some audits.\r\n\r\n\r\n\
+         \tNumber of audit messages discarded:\t17\r\n"
+    );
+
+    let msg = pefile.message(553, 0x0409)?.unwrap();
+    let inserts: Vec<Insert> = (1..=12).map(|i| Insert::from(format!("v{}", i))).collect();
+    let text = msg.format(&inserts);
+    assert!(text.starts_with("\tUser Name:\tv1\r\n\r\n\tDomain:\t%tv2\r\n"));
+    assert!(text.ends_with("\tCaller Process Name:\tv12\r\n\r\n"));
+    Ok(())
+}
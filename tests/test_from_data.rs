@@ -0,0 +1,42 @@
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::Arc;
+use libpefile::*;
+
+fn sample_file() -> PathBuf {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir))
+}
+
+fn message_count(pefile: &PEFile) -> std::io::Result<usize> {
+    Ok(pefile.messages_iter()?.filter_map(|r| r.ok()).count())
+}
+
+#[test]
+fn from_data() -> Result<(), std::io::Error> {
+    let expected = message_count(&PEFile::new(sample_file())?)?;
+    let buffer = std::fs::read(sample_file())?;
+
+    let pefile = PEFile::from_data(&buffer[..])?;
+    assert!(pefile.filename().is_none());
+    assert_eq!(expected, message_count(&pefile)?);
+
+    let shared: Arc<[u8]> = Arc::from(buffer.clone());
+    assert_eq!(expected, message_count(&PEFile::from_data(shared)?)?);
+
+    assert_eq!(expected, message_count(&PEFile::from_data(buffer)?)?);
+    Ok(())
+}
+
+#[test]
+fn from_reader() -> Result<(), std::io::Error> {
+    let mut buffer = vec![0xffu8; 16];
+    buffer.extend(std::fs::read(sample_file())?);
+
+    let mut reader = Cursor::new(buffer);
+    reader.set_position(16);
+    let pefile = PEFile::from_reader(&mut reader)?;
+    assert_eq!(pefile.sections().len(), 2);
+    assert_eq!(message_count(&PEFile::new(sample_file())?)?, message_count(&pefile)?);
+    Ok(())
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+
+const RT_CURSOR: u16 = 1;
+const RT_ICON: u16 = 3;
+const RT_GROUP_CURSOR: u16 = 12;
+const RT_GROUP_ICON: u16 = 14;
+
+fn u16s(values: &[u16]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// creates an RT_GROUP_ICON resource with entries of (width, bit count, size, id)
+fn icon_group(entries: &[(u8, u16, u32, u16)]) -> Vec<u8> {
+    let mut data = u16s(&[0, 1, entries.len() as u16]);
+    for &(width, bit_count, size, id) in entries {
+        data.extend_from_slice(&[width, width, 0, 0]);
+        data.extend(u16s(&[1, bit_count]));
+        data.extend_from_slice(&size.to_le_bytes());
+        data.extend(u16s(&[id]));
+    }
+    data
+}
+
+fn image_with(resources: ResourceBuilder) -> Result<PEFile> {
+    let resources = resources.build(0x1000);
+    let size = resources.data.len() as u32;
+    PEFile::from_data(
+        PEBuilder::new(false)
+            .directory(IMAGE_DIRECTORY_ENTRY_RESOURCE, 0x1000, size)
+            .section(".rsrc", resources)
+            .build(),
+    )
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+#[test]
+fn icon_to_ico() -> Result<()> {
+    let pefile = image_with(
+        ResourceBuilder::new()
+            .add(RT_ICON, 1u16, 0x0409, b"first image".to_vec())
+            .add(RT_ICON, 2u16, 0x0409, b"\x89PNG second".to_vec())
+            .add(RT_GROUP_ICON, "MAINICON", 0x0409, icon_group(&[(16, 4, 11, 1), (0, 32, 11, 2)])),
+    )?;
+    let groups = pefile.icon_groups()?;
+    assert_eq!(groups.len(), 1);
+    assert!(pefile.cursor_groups()?.is_empty());
+
+    let group = &groups[0];
+    assert_eq!(group.kind, IconKind::Icon);
+    assert_eq!(group.name, EntryIdentifier::Name("MAINICON".to_string()));
+    assert_eq!(group.lang_id, Some(0x0409));
+    assert_eq!(group.images[0].width, 16);
+    assert_eq!(group.images[1].width, 256);
+    assert_eq!(group.images[1].height, 256);
+
+    let ico = group.to_file(&pefile)?;
+    assert_eq!(&ico[0..6], &u16s(&[0, 1, 2])[..]);
+    assert_eq!(&ico[6..10], &[16, 16, 0, 0]);
+    assert_eq!(&ico[22..26], &[0, 0, 0, 0]);
+    assert_eq!(read_u16(&ico, 28), 32);
+    assert_eq!(read_u32(&ico, 30), 11);
+    let first = read_u32(&ico, 18) as usize;
+    let second = read_u32(&ico, 34) as usize;
+    assert_eq!(first, 6 + 2 * 16);
+    assert_eq!(&ico[first..first + 11], b"first image");
+    assert_eq!(&ico[second..], b"\x89PNG second");
+    Ok(())
+}
+
+#[test]
+fn cursor_to_cur() -> Result<()> {
+    let mut cursor = u16s(&[0, 2, 1, 32, 64, 1, 1]);
+    cursor.extend_from_slice(&14u32.to_le_bytes());
+    cursor.extend(u16s(&[7]));
+
+    let mut image = u16s(&[3, 5]);
+    image.extend_from_slice(b"monochrome");
+    let pefile = image_with(
+        ResourceBuilder::new()
+            .add(RT_CURSOR, 7u16, 0, image)
+            .add(RT_GROUP_CURSOR, 100u16, 0, cursor),
+    )?;
+    let groups = pefile.cursor_groups()?;
+    let group = &groups[0];
+    assert_eq!(group.kind, IconKind::Cursor);
+    assert_eq!(group.images[0].width, 32);
+    assert_eq!(group.images[0].height, 32);
+    assert_eq!(group.images[0].color_count, 2);
+
+    // the hotspot is moved from the image to the directory entry
+    let cur = group.to_file(&pefile)?;
+    assert_eq!(&cur[0..6], &u16s(&[0, 2, 1])[..]);
+    assert_eq!(&cur[6..10], &[32, 32, 2, 0]);
+    assert_eq!(read_u16(&cur, 10), 3);
+    assert_eq!(read_u16(&cur, 12), 5);
+    assert_eq!(read_u32(&cur, 14), 10);
+    assert_eq!(&cur[22..], b"monochrome");
+    Ok(())
+}
+
+#[test]
+fn missing_image() -> Result<()> {
+    let pefile = image_with(
+        ResourceBuilder::new()
+            .add(RT_ICON, 1u16, 0x0409, b"image".to_vec())
+            .add(RT_GROUP_ICON, 1u16, 0x0409, icon_group(&[(16, 4, 5, 1), (32, 4, 5, 2)])),
+    )?;
+    let groups = pefile.icon_groups()?;
+    assert!(matches!(groups[0].to_file(&pefile), Err(Error::InvalidResourceLayout(_))));
+
+    // a cursor group stored as icon group
+    let pefile = image_with(ResourceBuilder::new().add(RT_GROUP_ICON, 1u16, 0, u16s(&[0, 2, 0])))?;
+    assert!(matches!(pefile.icon_groups(), Err(Error::InvalidData { .. })));
+    Ok(())
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+
+/// creates an image which imports `CreateFileW` (by name) and ordinal 17 from KERNEL32.dll,
+/// and `MessageBoxW` from USER32.dll (without import lookup table)
+fn image_with_imports(is_64bit: bool) -> Vec<u8> {
+    let mut idata = SectionData::new(0x2000);
+    let thunk_size = if is_64bit { 8 } else { 4 };
+    let ordinal_flag = if is_64bit { 1u64 << 63 } else { 1u64 << 31 };
+
+    // two descriptors and a terminator, which we fill in later
+    let descriptors = idata.push(&[0; 60]);
+
+    let kernel32 = idata.push_cstring("KERNEL32.dll");
+    let user32 = idata.push_cstring("USER32.dll");
+    idata.align(2);
+    let create_file = idata.push_u16(0x00c4);
+    idata.push_cstring("CreateFileW");
+    idata.align(2);
+    let message_box = idata.push_u16(0x0283);
+    idata.push_cstring("MessageBoxW");
+    idata.align(8);
+
+    let push_thunks = |idata: &mut SectionData, thunks: &[u64]| {
+        let rva = idata.rva();
+        for thunk in thunks.iter().chain([0].iter()) {
+            idata.push(&thunk.to_le_bytes()[..thunk_size]);
+        }
+        rva
+    };
+    let kernel32_ilt = push_thunks(&mut idata, &[create_file as u64, ordinal_flag | 17]);
+    let kernel32_iat = push_thunks(&mut idata, &[create_file as u64, ordinal_flag | 17]);
+    let user32_iat = push_thunks(&mut idata, &[message_box as u64]);
+
+    for (idx, values) in [
+        [kernel32_ilt, 0, 0, kernel32, kernel32_iat],
+        [0, 0, 0, user32, user32_iat],
+    ].iter().enumerate() {
+        for (field, value) in values.iter().enumerate() {
+            idata.patch_u32(descriptors + (idx * 20 + field * 4) as u32, *value);
+        }
+    }
+
+    PEBuilder::new(is_64bit)
+        .directory(IMAGE_DIRECTORY_ENTRY_IMPORT, descriptors, 60)
+        .section(".idata", idata)
+        .build()
+}
+
+fn check_imports(is_64bit: bool) -> Result<()> {
+    let pefile = PEFile::from_data(image_with_imports(is_64bit))?;
+    assert_eq!(pefile.is_64bit(), is_64bit);
+
+    let dlls = pefile.imports()?.collect::<Result<Vec<_>>>()?;
+    assert_eq!(dlls.len(), 2);
+
+    assert_eq!(dlls[0].name, "KERNEL32.dll");
+    assert_eq!(dlls[0].functions.len(), 2);
+    assert_eq!(dlls[0].functions[0].symbol, ImportedSymbol::ByName { hint: 0xc4, name: "CreateFileW".to_string() });
+    assert_eq!(dlls[0].functions[1].symbol, ImportedSymbol::ByOrdinal(17));
+    assert_eq!(
+        dlls[0].functions[1].iat_rva - dlls[0].functions[0].iat_rva,
+        if is_64bit { 8 } else { 4 });
+    assert_eq!(dlls[0].functions[0].iat_rva, dlls[0].descriptor.FirstThunk);
+
+    assert_eq!(dlls[1].name, "USER32.dll");
+    assert_eq!(dlls[1].functions.len(), 1);
+    assert_eq!(dlls[1].functions[0].symbol.to_string(), "MessageBoxW");
+    Ok(())
+}
+
+#[test]
+fn imports_pe32() -> Result<()> {
+    check_imports(false)
+}
+
+#[test]
+fn imports_pe32_plus() -> Result<()> {
+    check_imports(true)
+}
+
+#[test]
+fn no_imports() -> Result<()> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let pefile = PEFile::new(format!("{}/samples/msaudite.dll", manifest_dir).into())?;
+    assert_eq!(pefile.imports()?.count(), 0);
+    Ok(())
+}
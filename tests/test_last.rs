@@ -11,3 +11,16 @@ fn last_last() -> Result<(), std::io::Error> {
     assert_eq!("Highest System-Defined Audit Message Value.\r\n\u{0}", msg.text);
     Ok(())
 }
+
+#[test]
+fn message_language() -> Result<(), std::io::Error> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let dll_file = PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir));
+    let pefile = PEFile::new(dll_file)?;
+
+    // the message table is named #1 and its only language is en-US
+    for msg in pefile.messages_iter()? {
+        assert_eq!(msg?.lang_id, 0x0409);
+    }
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use libpefile::*;
+use std::path::PathBuf;
+
+fn sample_file() -> PathBuf {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir))
+}
+
+fn messages(pefile: &PEFile) -> Result<Vec<(u32, String)>> {
+    pefile
+        .messages_iter()?
+        .map(|m| m.map(|m| (m.msg_id, m.text)))
+        .collect()
+}
+
+#[test]
+fn memory_layout() -> Result<()> {
+    let pefile = PEFile::new(sample_file())?;
+    let dump = PEFile::from_data_with_layout(pefile.map_image()?, ImageLayout::Memory)?;
+    assert_eq!(dump.layout(), ImageLayout::Memory);
+
+    let resources = pefile.directory(IMAGE_DIRECTORY_ENTRY::IMAGE_DIRECTORY_ENTRY_RESOURCE).unwrap();
+    let rva = resources.VirtualAddress as usize;
+    assert_eq!(dump.get_raw_address(rva), Some(rva));
+    assert_eq!(dump.get_resources_section(), pefile.get_resources_section());
+    assert_eq!(messages(&dump)?, messages(&pefile)?);
+    assert_eq!(dump.map_image()?, pefile.map_image()?);
+    Ok(())
+}
+
+#[test]
+fn detect_layout() -> Result<()> {
+    let pefile = PEFile::new_with_layout(sample_file(), ImageLayout::Detect)?;
+    assert_eq!(pefile.layout(), ImageLayout::File);
+
+    let dump = PEFile::from_data_with_layout(pefile.map_image()?, ImageLayout::Detect)?;
+    assert_eq!(dump.layout(), ImageLayout::Memory);
+    assert_eq!(messages(&dump)?, messages(&pefile)?);
+    Ok(())
+}
+
+#[test]
+fn detect_file_with_overlay() -> Result<()> {
+    // appending data to the file must not make it look like a memory dump
+    let mut data = std::fs::read(sample_file())?;
+    let size_of_image = PEFile::from_data(&data[..])?.image_optional_header().as_ref().unwrap().SizeOfImage();
+    data.resize(size_of_image as usize * 2, 0x90);
+
+    let pefile = PEFile::from_data_with_layout(data, ImageLayout::Detect)?;
+    assert_eq!(pefile.layout(), ImageLayout::File);
+    assert!(pefile.messages_iter()?.count() > 0);
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+use libpefile::*;
+
+fn sample_data() -> Vec<u8> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    std::fs::read(PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir))).unwrap()
+}
+
+/// parses the image and reads all messages. The result is not of interest,
+/// we only want to make sure that nothing panics
+fn parse(data: &[u8]) {
+    if let Ok(pefile) = PEFile::from_data(data) {
+        let _ = pefile.resources();
+        for section in pefile.sections() {
+            let _ = section.name();
+            let _ = pefile.get_raw_address(section.VirtualAddress as usize);
+        }
+        if let Ok(iter) = pefile.messages_iter() {
+            for msg in iter.take(100_000) {
+                let _ = msg;
+            }
+        }
+    }
+}
+
+/// simple xorshift generator, so that the test is reproducible without extra dependencies
+struct XorShift(u64);
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+#[test]
+fn truncated_samples() {
+    let data = sample_data();
+
+    // every possible length inside the headers and the resource directory, and some beyond
+    for len in (0..0x1000).chain((0x1000..data.len()).step_by(997)) {
+        parse(&data[..len]);
+    }
+}
+
+#[test]
+fn fuzzed_samples() {
+    let original = sample_data();
+    let mut rng = XorShift(0x5eed_1e55_c0ff_ee00);
+
+    for _ in 0..500 {
+        let mut data = original.clone();
+        let changes = 1 + rng.next() % 16;
+        for _ in 0..changes {
+            // most of the interesting structures are at the beginning of the
+            // file (headers) and of the resources section (at offset 0x400)
+            let idx = match rng.next() % 3 {
+                0 => rng.next() as usize % 0x200,
+                1 => 0x400 + rng.next() as usize % 0x200,
+                _ => rng.next() as usize % data.len(),
+            };
+            data[idx] = rng.next() as u8;
+        }
+        parse(&data);
+    }
+}
+
+#[test]
+fn hostile_values() {
+    let original = sample_data();
+    let nt_offset = 0xb8;
+    let section_table = nt_offset + 24 + 224;
+    let resources = 0x400;
+
+    let patches: Vec<(usize, &[u8])> = vec![
+        // e_lfanew points outside of the file
+        (0x3c, &[0xff, 0xff, 0xff, 0xff]),
+        // huge number of sections
+        (nt_offset + 6, &[0xff, 0xff]),
+        // huge NumberOfRvaAndSizes
+        (nt_offset + 24 + 92, &[0xff, 0xff, 0xff, 0xff]),
+        // section with a virtual size which overflows
+        (section_table + 40 + 8, &[0xff, 0xff, 0xff, 0xff]),
+        // non-UTF-8 section name
+        (section_table, &[0xff, 0xfe, 0xfd, 0xfc, 0xfb, 0xfa, 0xf9, 0xf8]),
+        // resource directory with the maximum number of entries
+        (resources + 12, &[0xff, 0xff, 0xff, 0xff]),
+        // named resource entry with the name outside of the section
+        (resources + 16, &[0xf0, 0xff, 0xff, 0xff]),
+    ];
+
+    for (offset, patch) in patches {
+        let mut data = original.clone();
+        data[offset..offset + patch.len()].copy_from_slice(patch);
+        parse(&data);
+    }
+}
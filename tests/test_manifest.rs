@@ -0,0 +1,116 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+
+const RT_MANIFEST: u16 = 24;
+
+const MANIFEST: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<assembly xmlns="urn:schemas-microsoft-com:asm.v1" manifestVersion="1.0" xmlns:asmv3="urn:schemas-microsoft-com:asm.v3">
+  <trustInfo xmlns="urn:schemas-microsoft-com:asm.v3">
+    <security>
+      <requestedPrivileges>
+        <requestedExecutionLevel level="requireAdministrator" uiAccess="false"/>
+      </requestedPrivileges>
+    </security>
+  </trustInfo>
+  <dependency>
+    <dependentAssembly>
+      <assemblyIdentity type="win32" name="Microsoft.Windows.Common-Controls" version="6.0.0.0"
+        processorArchitecture="*" publicKeyToken="6595b64144ccf1df" language="*"/>
+    </dependentAssembly>
+  </dependency>
+  <compatibility xmlns="urn:schemas-microsoft-com:compatibility.v1">
+    <application>
+      <supportedOS Id="{35138b9a-5d96-4fbd-8e2d-a2440225f93a}"/>
+      <supportedOS Id="{8e0f7a12-bfb3-4fe8-b9a5-48fd50a15a9a}"/>
+    </application>
+  </compatibility>
+  <asmv3:application>
+    <asmv3:windowsSettings>
+      <dpiAware xmlns="http://schemas.microsoft.com/SMI/2005/WindowsSettings">true/pm</dpiAware>
+      <dpiAwareness xmlns="http://schemas.microsoft.com/SMI/2016/WindowsSettings">PerMonitorV2</dpiAwareness>
+      <autoElevate xmlns="http://schemas.microsoft.com/SMI/2005/WindowsSettings">true</autoElevate>
+    </asmv3:windowsSettings>
+  </asmv3:application>
+</assembly>"#;
+
+fn image_with_manifest(data: Vec<u8>) -> Result<PEFile> {
+    let resources = ResourceBuilder::new()
+        .add(RT_MANIFEST, 1u16, 0x0409, data)
+        .build(0x1000);
+    let size = resources.data.len() as u32;
+    PEFile::from_data(
+        PEBuilder::new(true)
+            .directory(IMAGE_DIRECTORY_ENTRY_RESOURCE, 0x1000, size)
+            .section(".rsrc", resources)
+            .build(),
+    )
+}
+
+fn check_manifest(manifest: &AssemblyManifest) {
+    assert_eq!(manifest.name, EntryIdentifier::Id(1));
+    assert_eq!(manifest.xml, MANIFEST);
+    assert_eq!(manifest.requested_execution_level.as_deref(), Some("requireAdministrator"));
+    assert_eq!(manifest.ui_access, Some(false));
+    assert_eq!(manifest.auto_elevate, Some(true));
+    assert_eq!(manifest.dpi_aware.as_deref(), Some("true/pm"));
+    assert_eq!(manifest.dpi_awareness.as_deref(), Some("PerMonitorV2"));
+    assert_eq!(
+        manifest.supported_os,
+        vec!["{35138b9a-5d96-4fbd-8e2d-a2440225f93a}", "{8e0f7a12-bfb3-4fe8-b9a5-48fd50a15a9a}"]
+    );
+    assert_eq!(
+        manifest.dependent_assemblies,
+        vec![DependentAssembly {
+            name: "Microsoft.Windows.Common-Controls".to_string(),
+            version: Some("6.0.0.0".to_string()),
+            assembly_type: Some("win32".to_string()),
+            processor_architecture: Some("*".to_string()),
+            public_key_token: Some("6595b64144ccf1df".to_string()),
+            language: Some("*".to_string()),
+        }]
+    );
+}
+
+#[test]
+fn utf8_manifest() -> Result<()> {
+    // without byte order mark, but padded
+    let mut data = MANIFEST.as_bytes().to_vec();
+    data.extend_from_slice(b"\r\n    \0\0");
+    check_manifest(&image_with_manifest(data)?.manifest()?.unwrap());
+
+    let mut data = b"\xef\xbb\xbf".to_vec();
+    data.extend_from_slice(MANIFEST.as_bytes());
+    check_manifest(&image_with_manifest(data)?.manifest()?.unwrap());
+    Ok(())
+}
+
+#[test]
+fn utf16_manifest() -> Result<()> {
+    let mut data = vec![0xff, 0xfe];
+    data.extend(MANIFEST.encode_utf16().flat_map(|c| c.to_le_bytes()));
+    check_manifest(&image_with_manifest(data)?.manifest()?.unwrap());
+
+    let mut data = vec![0xfe, 0xff];
+    data.extend(MANIFEST.encode_utf16().flat_map(|c| c.to_be_bytes()));
+    check_manifest(&image_with_manifest(data)?.manifest()?.unwrap());
+    Ok(())
+}
+
+#[test]
+fn minimal_and_invalid_manifests() -> Result<()> {
+    let pefile = image_with_manifest(b"<assembly manifestVersion=\"1.0\"/>".to_vec())?;
+    let manifest = pefile.manifest()?.unwrap();
+    assert!(manifest.requested_execution_level.is_none());
+    assert!(manifest.auto_elevate.is_none());
+    assert!(manifest.supported_os.is_empty());
+    assert!(manifest.dependent_assemblies.is_empty());
+
+    let pefile = image_with_manifest(b"<assembly><trustInfo></assembly>".to_vec())?;
+    assert!(matches!(pefile.manifest(), Err(Error::InvalidData { .. })));
+
+    let pefile = PEFile::from_data(PEBuilder::new(true).build())?;
+    assert!(pefile.manifest()?.is_none());
+    Ok(())
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+use std::path::PathBuf;
+
+fn sample() -> Result<PEFile> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    PEFile::new(PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir)))
+}
+
+#[test]
+fn map_sample() -> Result<()> {
+    let pefile = sample()?;
+    let image = pefile.map_image()?;
+    let optional_header = pefile.image_optional_header().as_ref().unwrap();
+    assert_eq!(image.len(), optional_header.SizeOfImage() as usize);
+
+    let size_of_headers = optional_header.SizeOfHeaders() as usize;
+    assert_eq!(&image[..size_of_headers], &pefile.full_image()[..size_of_headers]);
+
+    for section in pefile.sections() {
+        let rva = section.VirtualAddress as usize;
+        let size = (section.Misc as usize).min(section.SizeOfRawData as usize);
+        let raw = pefile.get_raw_address(rva).unwrap();
+        assert_eq!(&image[rva..rva + size], &pefile.full_image()[raw..raw + size]);
+    }
+    Ok(())
+}
+
+#[test]
+fn virtual_tail_is_zeroed() -> Result<()> {
+    let mut text = SectionData::new(0x1000);
+    text.push(&[0xcc; 0x300]);
+    let mut data = SectionData::new(0x2000);
+    data.push(&[0xaa; 0x10]);
+
+    // .text has 0x400 bytes of raw data, but only 0x100 bytes are mapped into the first page.
+    // .data has 0x200 bytes of raw data, and a virtual size of 0x1800 bytes.
+    let pefile = PEFile::from_data(
+        PEBuilder::new(false)
+            .section(".text", text)
+            .section_with_size(".data", data, 0x1800)
+            .build(),
+    )?;
+    let image = pefile.map_image()?;
+    assert_eq!(image.len(), 0x4000);
+
+    // the raw data is mapped up to the end of the page
+    assert!(image[0x1000..0x1300].iter().all(|b| *b == 0xcc));
+    assert!(image[0x1300..0x2000].iter().all(|b| *b == 0));
+    assert!(image[0x2000..0x2010].iter().all(|b| *b == 0xaa));
+    assert!(image[0x2010..].iter().all(|b| *b == 0));
+    Ok(())
+}
+
+#[test]
+fn raw_data_beyond_virtual_size() -> Result<()> {
+    let mut text = SectionData::new(0x1000);
+    text.push(&[0xcc; 0x1800]);
+    let mut data = SectionData::new(0x2000);
+    data.push(&[0xaa; 0x10]);
+
+    // the raw data of .text is larger than its (aligned) virtual size, so it must not overwrite .data
+    let pefile = PEFile::from_data(
+        PEBuilder::new(true)
+            .section_with_size(".text", text, 0x100)
+            .section(".data", data)
+            .build(),
+    )?;
+    let image = pefile.map_image()?;
+    assert!(image[0x1000..0x2000].iter().all(|b| *b == 0xcc));
+    assert!(image[0x2000..0x2010].iter().all(|b| *b == 0xaa));
+    Ok(())
+}
+
+#[test]
+fn size_of_image_beyond_sections() -> Result<()> {
+    let mut text = SectionData::new(0x1000);
+    text.push(&[0xcc; 0x10]);
+    let mut image = PEBuilder::new(true).section(".text", text).build();
+
+    // SizeOfImage of the optional header
+    image[0x90..0x94].copy_from_slice(&0xffff_0000u32.to_le_bytes());
+    let pefile = PEFile::from_data(image)?;
+    assert!(matches!(pefile.map_image(), Err(Error::InvalidData { offset: 0x90, .. })));
+    Ok(())
+}
+
+#[test]
+fn map_relocated() -> Result<()> {
+    let mut data = SectionData::new(0x1000);
+    data.push_u32(0x0040_1234);
+    let mut reloc = SectionData::new(0x2000);
+    reloc.push_u32(0x1000);
+    reloc.push_u32(12);
+    reloc.push_u16(0x3000);
+    reloc.push_u16(0);
+
+    let pefile = PEFile::from_data(
+        PEBuilder::new(false)
+            .directory(IMAGE_DIRECTORY_ENTRY_BASERELOC, 0x2000, 12)
+            .section(".data", data)
+            .section(".reloc", reloc)
+            .build(),
+    )?;
+    let image = pefile.map_image_at(0x1000_0000)?;
+    assert_eq!(&image[0x1000..0x1004], &0x1000_1234u32.to_le_bytes());
+    Ok(())
+}
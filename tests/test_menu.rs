@@ -0,0 +1,111 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+
+const RT_MENU: u16 = 4;
+const RT_ACCELERATOR: u16 = 9;
+
+const MF_GRAYED: u16 = 0x01;
+const MF_POPUP: u16 = 0x10;
+const MF_END: u16 = 0x80;
+
+fn image_with(resources: ResourceBuilder) -> Result<PEFile> {
+    let resources = resources.build(0x1000);
+    let size = resources.data.len() as u32;
+    PEFile::from_data(
+        PEBuilder::new(false)
+            .directory(IMAGE_DIRECTORY_ENTRY_RESOURCE, 0x1000, size)
+            .section(".rsrc", resources)
+            .build(),
+    )
+}
+
+#[test]
+fn normal_menu() -> Result<()> {
+    let template = TemplateBuilder::default()
+        .u16(0).u16(0)
+        .u16(MF_POPUP).string("&File")
+            .u16(0).u16(100).string("&Open\tCtrl+O")
+            .u16(0).u16(0).string("")
+            .u16(MF_POPUP).string("Recent")
+                .u16(MF_END | MF_GRAYED).u16(101).string("(empty)")
+            .u16(MF_END).u16(102).string("E&xit")
+        .u16(MF_END).u16(200).string("&Help");
+    let pefile = image_with(ResourceBuilder::new().add(RT_MENU, 1u16, 0x0409, template.0))?;
+    let menus = pefile.menus()?;
+    assert_eq!(menus.len(), 1);
+
+    let menu = &menus[0];
+    assert!(!menu.extended);
+    assert_eq!(menu.items.len(), 2);
+    let file = &menu.items[0];
+    assert!(file.popup);
+    assert_eq!(file.text, "&File");
+    assert_eq!(file.children.len(), 4);
+    assert_eq!(file.children[0].id, 100);
+    assert_eq!(file.children[0].text, "&Open\tCtrl+O");
+    assert!(file.children[1].is_separator());
+    let recent = &file.children[2];
+    assert_eq!(recent.children.len(), 1);
+    assert_eq!(recent.children[0].flags, MF_GRAYED as u32);
+    assert_eq!(file.children[3].id, 102);
+    assert_eq!(menu.items[1].id, 200);
+    assert!(!menu.items[1].is_separator());
+    Ok(())
+}
+
+#[test]
+fn extended_menu() -> Result<()> {
+    let template = TemplateBuilder::default()
+        .u16(1).u16(4).u32(500)
+        .u32(0).u32(0).u32(10).u16(0x01).string("&Edit").align().u32(501)
+            .align().u32(0).u32(0x08).u32(11).u16(0).string("&Undo")
+            .align().u32(0x800).u32(0).u32(0).u16(0).string("")
+            .align().u32(0).u32(0x03).u32(12).u16(0x80).string("&Paste")
+        .align().u32(0x200).u32(0).u32(13).u16(0x80).string("&About");
+    let pefile = image_with(ResourceBuilder::new().add(RT_MENU, "MAINMENU", 0, template.0))?;
+    let menu = &pefile.menus()?[0];
+
+    assert!(menu.extended);
+    assert_eq!(menu.help_id, 500);
+    assert_eq!(menu.name, EntryIdentifier::Name("MAINMENU".to_string()));
+    assert_eq!(menu.items.len(), 2);
+    let edit = &menu.items[0];
+    assert!(edit.popup);
+    assert_eq!((edit.id, edit.help_id), (10, 501));
+    assert_eq!(edit.children.len(), 3);
+    assert_eq!(edit.children[0].state, 0x08);
+    assert!(edit.children[1].is_separator());
+    assert_eq!(edit.children[2].text, "&Paste");
+    assert_eq!(menu.items[1].flags, 0x200);
+
+    // the popup is never closed
+    let truncated = TemplateBuilder::default()
+        .u16(1).u16(4).u32(0)
+        .u32(0).u32(0).u32(10).u16(0x01).string("&Edit").align().u32(0);
+    let pefile = image_with(ResourceBuilder::new().add(RT_MENU, 1u16, 0, truncated.0))?;
+    assert!(matches!(pefile.menus(), Err(Error::Truncated { .. })));
+    Ok(())
+}
+
+#[test]
+fn accelerators() -> Result<()> {
+    let table = TemplateBuilder::default()
+        .u16(0x09).u16(0x4f).u16(100).u16(0)
+        .u16(0x15).u16(0x73).u16(102).u16(0)
+        .u16(0x80).u16(b'?' as u16).u16(200).u16(0);
+    let pefile = image_with(ResourceBuilder::new().add(RT_ACCELERATOR, 1u16, 0x0409, table.0))?;
+    let tables = pefile.accelerator_tables()?;
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].lang_id, Some(0x0409));
+
+    let accelerators = &tables[0].accelerators;
+    assert_eq!(accelerators.len(), 3);
+    assert!(accelerators[0].is_virtual_key() && accelerators[0].control());
+    assert!(!accelerators[0].shift() && !accelerators[0].alt());
+    assert_eq!((accelerators[0].key, accelerators[0].id), (0x4f, 100));
+    assert!(accelerators[1].alt() && accelerators[1].shift());
+    assert_eq!(accelerators[2], Accelerator { flags: 0, key: b'?' as u16, id: 200 });
+    Ok(())
+}
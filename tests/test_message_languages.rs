@@ -0,0 +1,76 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+use std::path::PathBuf;
+
+const RT_MESSAGETABLE: u16 = 11;
+
+fn multilingual_image() -> Result<PEFile> {
+    let resources = ResourceBuilder::new()
+        .add(RT_MESSAGETABLE, 1, 0x0409, message_table(&[(1, &["one", "two"]), (10, &["ten"])]))
+        .add(RT_MESSAGETABLE, 1, 0x0407, message_table(&[(1, &["eins", "zwei"])]))
+        .build(0x1000);
+    let size = resources.data.len() as u32;
+    PEFile::from_data(
+        PEBuilder::new(false)
+            .directory(IMAGE_DIRECTORY_ENTRY_RESOURCE, 0x1000, size)
+            .section(".rsrc", resources)
+            .build(),
+    )
+}
+
+fn texts<I: Iterator<Item = Result<Message>>>(messages: I) -> Result<Vec<(u32, String)>> {
+    messages
+        .map(|m| m.map(|m| (m.msg_id, m.text.trim_end_matches('\0').to_string())))
+        .collect()
+}
+
+#[test]
+fn languages() -> Result<()> {
+    let pefile = multilingual_image()?;
+    assert_eq!(pefile.message_languages()?, vec![0x0407, 0x0409]);
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let msaudite = PEFile::new(PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir)))?;
+    assert_eq!(msaudite.message_languages()?, vec![0x0409]);
+    Ok(())
+}
+
+#[test]
+fn iter_for_language() -> Result<()> {
+    let pefile = multilingual_image()?;
+    assert_eq!(
+        texts(pefile.messages_iter_for_language(0x0409)?)?,
+        vec![(1, "one".to_string()), (2, "two".to_string()), (10, "ten".to_string())]
+    );
+    assert_eq!(
+        texts(pefile.messages_iter_for_language(0x0407)?)?,
+        vec![(1, "eins".to_string()), (2, "zwei".to_string())]
+    );
+    assert_eq!(pefile.messages_iter_for_language(0x040c)?.count(), 0);
+
+    for msg in pefile.messages_iter_for_language(0x0407)? {
+        let msg = msg?;
+        assert_eq!(msg.lang_id, 0x0407);
+        assert_eq!(msg.language().name(), Some("de-DE"));
+    }
+
+    assert_eq!(pefile.message(10, 0x0409)?.unwrap().text.trim_end_matches('\0'), "ten");
+    assert!(pefile.message(10, 0x0407)?.is_none());
+    assert_eq!(pefile.message_with_fallback(10, 0x0807)?.unwrap().lang_id, 0x0409);
+    assert_eq!(pefile.message_with_fallback(2, 0x0807)?.unwrap().text.trim_end_matches('\0'), "zwei");
+    Ok(())
+}
+
+#[test]
+fn langid_helpers() {
+    let lang = LangId::from(0x0c07u32);
+    assert_eq!(lang.primary_language(), 0x07);
+    assert_eq!(lang.sub_language(), 0x03);
+    assert_eq!(LangId::new(0x07, 0x03), lang);
+    assert_eq!(lang.to_string(), "de-AT");
+    assert_eq!(LangId::EN_US.name(), Some("en-US"));
+    assert_eq!(LangId::NEUTRAL.name(), None);
+    assert_eq!(LangId(0x7fff).to_string(), "0x7fff");
+}
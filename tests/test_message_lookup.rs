@@ -0,0 +1,61 @@
+use libpefile::*;
+use std::path::PathBuf;
+
+fn sample() -> Result<PEFile> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    PEFile::new(PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir)))
+}
+
+#[test]
+fn lookup_all_messages() -> Result<()> {
+    let pefile = sample()?;
+    let mut count = 0;
+    for msg in pefile.messages_iter()? {
+        let msg = msg?;
+        assert_eq!(msg.lang_id, 0x0409);
+        let found = pefile.message(msg.msg_id, msg.lang_id)?.unwrap();
+        assert_eq!(found.msg_id, msg.msg_id);
+        assert_eq!(found.text, msg.text);
+        count += 1;
+    }
+    assert!(count > 0);
+    Ok(())
+}
+
+#[test]
+fn lookup_missing_message() -> Result<()> {
+    let pefile = sample()?;
+    assert!(pefile.message(4096, 0x0409)?.is_none());
+    assert!(pefile.message(u32::MAX, 0x0409)?.is_none());
+
+    // there is no german message table
+    assert!(pefile.message(2, 0x0407)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn lookup_with_fallback() -> Result<()> {
+    let pefile = sample()?;
+    let msg = pefile.message_with_fallback(2, 0x0407)?.unwrap();
+    assert_eq!(msg.lang_id, 0x0409);
+    assert_eq!(msg.msg_id, 2);
+    assert_eq!(msg.text, "Logon/Logoff\r\n\0\0");
+    assert!(pefile.message_with_fallback(4096, 0x0407)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn lookup_from_threads() -> Result<()> {
+    // the cached message tables must not prevent sharing an image between threads
+    let pefile = sample()?;
+    std::thread::scope(|scope| {
+        let lookups: Vec<_> = (0..4)
+            .map(|_| scope.spawn(|| pefile.message(2, 0x0409)))
+            .collect();
+        for lookup in lookups {
+            let msg = lookup.join().unwrap()?.unwrap();
+            assert_eq!(msg.text, "Logon/Logoff\r\n\0\0");
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+use std::path::PathBuf;
+
+const RT_MESSAGETABLE: u16 = 11;
+
+/// creates an MUI resource, which contains only a language name and a fallback language name
+fn mui_resource(file_type: u32, checksum: u8, language: Option<&str>, fallback: Option<&str>) -> Vec<u8> {
+    let mut data = vec![0u8; 0x84];
+    data[0..4].copy_from_slice(&0xfecdfecdu32.to_le_bytes());
+    data[8..12].copy_from_slice(&0x10000u32.to_le_bytes());
+    data[0x10..0x14].copy_from_slice(&file_type.to_le_bytes());
+    data[0x18..0x1c].copy_from_slice(&2u32.to_le_bytes());
+    data[0x2c..0x3c].copy_from_slice(&[checksum; 16]);
+    for (field, name) in [(0x74, language), (0x7c, fallback)] {
+        if let Some(name) = name {
+            let offset = data.len() as u32;
+            data.extend(name.encode_utf16().chain([0, 0]).flat_map(|c| c.to_le_bytes()));
+            let size = data.len() as u32 - offset;
+            data[field..field + 4].copy_from_slice(&offset.to_le_bytes());
+            data[field + 4..field + 8].copy_from_slice(&size.to_le_bytes());
+        }
+    }
+    let size = data.len() as u32;
+    data[4..8].copy_from_slice(&size.to_le_bytes());
+    data
+}
+
+fn image(mui: Vec<u8>, message: &str) -> Vec<u8> {
+    let resources = ResourceBuilder::new()
+        .add("MUI", 1u16, 0x0409, mui)
+        .add(RT_MESSAGETABLE, 1u16, 0x0409, message_table(&[(1, &[message])]))
+        .build(0x1000);
+    let size = resources.data.len() as u32;
+    PEBuilder::new(true)
+        .directory(IMAGE_DIRECTORY_ENTRY_RESOURCE, 0x1000, size)
+        .section(".rsrc", resources)
+        .build()
+}
+
+/// creates a directory which contains `base.dll` and the given `.mui` files
+fn mui_directory(name: &str, mui_files: &[(&str, Vec<u8>)]) -> std::io::Result<PathBuf> {
+    let root = std::env::temp_dir().join(format!("libpefile-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root)?;
+    std::fs::write(root.join("base.dll"), image(mui_resource(0x11, 0xaa, None, Some("en-US")), "neutral"))?;
+    for (language, data) in mui_files {
+        std::fs::create_dir_all(root.join(language))?;
+        std::fs::write(root.join(language).join("base.dll.mui"), data)?;
+    }
+    Ok(root)
+}
+
+fn first_message(resolver: &MuiResolver) -> Result<String> {
+    let msg = resolver.messages_iter()?.next().unwrap()?;
+    Ok(msg.text.trim_end_matches('\0').to_string())
+}
+
+#[test]
+fn msaudite_mui_config() -> Result<()> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let pefile = PEFile::new(PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir)))?;
+    let config = pefile.mui_config()?.unwrap();
+
+    assert!(config.is_language_neutral());
+    assert_eq!(config.fallback_language.as_deref(), Some("en-US"));
+    assert_eq!(config.language, None);
+    assert_eq!(config.main_type_names, vec!["MUI"]);
+    assert_eq!(config.main_type_ids, vec![11, 16]);
+    assert_eq!(config.mui_type_ids, vec![11, 16]);
+    assert_eq!(&config.checksum[0..4], &[0xa8, 0x96, 0x0f, 0xed]);
+    Ok(())
+}
+
+#[test]
+fn resolve_mui_file() -> Result<()> {
+    let root = mui_directory("resolve", &[
+        ("de-DE", image(mui_resource(0x12, 0xaa, Some("de-DE"), None), "deutsch")),
+        ("en-US", image(mui_resource(0x12, 0xaa, Some("en-US"), None), "english")),
+    ])?;
+
+    let resolver = MuiResolver::new(PEFile::new(root.join("base.dll"))?, &root, &["de-DE"])?;
+    assert_eq!(resolver.mui().unwrap().mui_config()?.unwrap().language.as_deref(), Some("de-DE"));
+    assert_eq!(first_message(&resolver)?, "deutsch");
+
+    // the fallback language is used if there is no file for the requested language
+    let resolver = MuiResolver::new(PEFile::new(root.join("base.dll"))?, &root, &["fr-FR"])?;
+    assert_eq!(first_message(&resolver)?, "english");
+
+    // an internal fallback language is taken from the language neutral file
+    let mut internal = mui_resource(0x11, 0xaa, None, Some("en-US"));
+    internal[0x18..0x1c].copy_from_slice(&1u32.to_le_bytes());
+    std::fs::write(root.join("internal.dll"), image(internal, "neutral"))?;
+    std::fs::create_dir_all(root.join("en-US"))?;
+    std::fs::copy(root.join("en-US").join("base.dll.mui"), root.join("en-US").join("internal.dll.mui"))?;
+    let resolver = MuiResolver::new(PEFile::new(root.join("internal.dll"))?, &root, &["fr-FR"])?;
+    assert!(resolver.mui().is_none());
+    assert_eq!(first_message(&resolver)?, "neutral");
+
+    std::fs::remove_dir_all(root)?;
+    Ok(())
+}
+
+#[test]
+fn reject_mismatching_mui_file() -> Result<()> {
+    let root = mui_directory("mismatch", &[
+        ("de-DE", image(mui_resource(0x12, 0xbb, Some("de-DE"), None), "deutsch")),
+        ("en-US", image(mui_resource(0x11, 0xaa, Some("en-US"), None), "english")),
+    ])?;
+
+    let resolver = MuiResolver::new(PEFile::new(root.join("base.dll"))?, &root, &["de-DE"])?;
+    assert!(resolver.mui().is_none());
+    assert_eq!(first_message(&resolver)?, "neutral");
+
+    let base = PEFile::from_data(image(mui_resource(0x11, 0xaa, None, Some("en-US")), "neutral"))?;
+    assert!(matches!(MuiResolver::new(base, &root, &["de-DE"]), Err(Error::MissingFileName)));
+
+    std::fs::remove_dir_all(root)?;
+    Ok(())
+}
+
+#[test]
+fn skip_broken_mui_file() -> Result<()> {
+    let mut broken_mui = mui_resource(0x12, 0xaa, Some("fr-FR"), None);
+    broken_mui[0] = 0;
+    let root = mui_directory("broken", &[
+        ("de-DE", b"not a PE file".to_vec()),
+        ("fr-FR", image(broken_mui, "francais")),
+        ("en-US", image(mui_resource(0x12, 0xaa, Some("en-US"), None), "english")),
+    ])?;
+
+    let resolver = MuiResolver::new(PEFile::new(root.join("base.dll"))?, &root, &["de-DE", "fr-FR"])?;
+    assert_eq!(first_message(&resolver)?, "english");
+
+    std::fs::remove_dir_all(root)?;
+    Ok(())
+}
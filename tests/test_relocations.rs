@@ -0,0 +1,138 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+
+/// creates an image with a `.data` section, which contains `data`,
+/// and a relocation block with `entries` for the page of `.data`
+fn image_with_relocations(is_64bit: bool, machine: u16, image_base: u64, data: &[u8], entries: &[u16]) -> Vec<u8> {
+    let mut section = SectionData::new(0x1000);
+    section.push(data);
+
+    let mut reloc = SectionData::new(0x2000);
+    reloc.push_u32(0x1000);
+    reloc.push_u32(8 + 2 * entries.len() as u32);
+    for entry in entries {
+        reloc.push_u16(*entry);
+    }
+    let size = reloc.data.len() as u32;
+
+    PEBuilder::new(is_64bit)
+        .machine(machine)
+        .image_base(image_base)
+        .directory(IMAGE_DIRECTORY_ENTRY_BASERELOC, 0x2000, size)
+        .section(".data", section)
+        .section(".reloc", reloc)
+        .build()
+}
+
+/// lays out `.data` like in memory, relocates it and returns the relocated contents of `.data`
+fn relocate(pefile: &PEFile, new_base: u64) -> Result<Vec<u8>> {
+    let mut image = vec![0u8; 0x3000];
+    let section = &pefile.sections()[0];
+    let raw = &pefile.full_image()[section.PointerToRawData as usize..][..section.Misc as usize];
+    image[0x1000..0x1000 + raw.len()].copy_from_slice(raw);
+
+    pefile.apply_relocations(&mut image, new_base)?;
+    Ok(image[0x1000..0x1000 + raw.len()].to_vec())
+}
+
+#[test]
+fn highlow() -> Result<()> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&0x0040_1234u32.to_le_bytes());
+    data.extend_from_slice(&0x0040u16.to_le_bytes());
+    data.extend_from_slice(&0x1234u16.to_le_bytes());
+
+    // HIGHLOW at 0, HIGH at 4, LOW at 6, and padding
+    let pefile = PEFile::from_data(image_with_relocations(false, 0x14c, 0x40_0000, &data, &[0x3000, 0x1004, 0x2006, 0x0000]))?;
+
+    let blocks = pefile.relocations()?.collect::<Result<Vec<_>>>()?;
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0].header.VirtualAddress, 0x1000);
+    let kinds: Vec<_> = blocks[0].relocations.iter().map(|r| (r.rva, r.kind)).collect();
+    assert_eq!(kinds, vec![
+        (0x1000, RelocationType::HighLow),
+        (0x1004, RelocationType::High),
+        (0x1006, RelocationType::Low),
+        (0x1000, RelocationType::Absolute)]);
+
+    let relocated = relocate(&pefile, 0x1002_0000)?;
+    assert_eq!(&relocated[0..4], &0x1002_1234u32.to_le_bytes());
+    assert_eq!(&relocated[4..6], &0x1002u16.to_le_bytes());
+    assert_eq!(&relocated[6..8], &0x1234u16.to_le_bytes());
+    Ok(())
+}
+
+#[test]
+fn dir64() -> Result<()> {
+    let data = 0x1_4000_1234u64.to_le_bytes();
+    let pefile = PEFile::from_data(image_with_relocations(true, 0x8664, 0x1_4000_0000, &data, &[0xa000]))?;
+    let relocated = relocate(&pefile, 0x7ff6_0000_0000)?;
+    assert_eq!(&relocated[..], &0x7ff6_0000_1234u64.to_le_bytes());
+    Ok(())
+}
+
+fn thumb_mov(opcode: u32, imm16: u32) -> u32 {
+    // first halfword: 11110 i 10x1x0 imm4, second halfword: 0 imm3 Rd imm8
+    let hw1 = opcode | ((imm16 >> 1) & 0x0400) | (imm16 >> 12);
+    let hw2 = ((imm16 << 4) & 0x7000) | (imm16 & 0x00ff);
+    hw1 | (hw2 << 16)
+}
+
+fn arm_mov(opcode: u32, imm16: u32) -> u32 {
+    opcode | ((imm16 & 0xf000) << 4) | (imm16 & 0x0fff)
+}
+
+#[test]
+fn mov32() -> Result<()> {
+    let mut data = Vec::new();
+    for instruction in [
+        thumb_mov(0xf240, 0x1234), thumb_mov(0xf2c0, 0x0040),
+        arm_mov(0xe300_0000, 0x1234), arm_mov(0xe340_0000, 0x0040),
+    ].iter() {
+        data.extend_from_slice(&instruction.to_le_bytes());
+    }
+
+    let pefile = PEFile::from_data(image_with_relocations(false, 0x1c4, 0x40_0000, &data, &[0x7000, 0x5008]))?;
+    let kinds: Vec<_> = pefile.relocations()?.next().unwrap()?.relocations.iter().map(|r| r.kind).collect();
+    assert_eq!(kinds, vec![RelocationType::ThumbMov32, RelocationType::ArmMov32]);
+
+    let relocated = relocate(&pefile, 0x1000_0000)?;
+    let mut expected = Vec::new();
+    for instruction in [
+        thumb_mov(0xf240, 0x1234), thumb_mov(0xf2c0, 0x1000),
+        arm_mov(0xe300_0000, 0x1234), arm_mov(0xe340_0000, 0x1000),
+    ].iter() {
+        expected.extend_from_slice(&instruction.to_le_bytes());
+    }
+    assert_eq!(relocated, expected);
+    Ok(())
+}
+
+#[test]
+fn unsupported_relocation() -> Result<()> {
+    // type 5 is no ARM_MOV32 relocation in an x86 image
+    let pefile = PEFile::from_data(image_with_relocations(false, 0x14c, 0x40_0000, &[0; 8], &[0x5000]))?;
+    assert_eq!(pefile.relocations()?.next().unwrap()?.relocations[0].kind, RelocationType::Unsupported(5));
+    assert!(relocate(&pefile, 0x1000_0000).is_err());
+    Ok(())
+}
+
+#[test]
+fn unlisted_machine() -> Result<()> {
+    let pefile = PEFile::from_data(image_with_relocations(true, 0xa641, 0x40_0000, &[0; 8], &[0xa000]))?;
+    let header = pefile.image_file_header();
+    assert_eq!(header.machine(), Some(IMAGE_FILE_HEADER_Machine::IMAGE_FILE_MACHINE_ARM64EC));
+    assert!(!header.is_arm());
+    assert_eq!(pefile.relocations()?.next().unwrap()?.relocations[0].kind, RelocationType::Dir64);
+
+    // machines which are not known by this crate can still be parsed
+    let pefile = PEFile::from_data(image_with_relocations(false, 0x1234, 0x40_0000, &[0; 8], &[0x3000]))?;
+    let header = pefile.image_file_header();
+    assert_eq!(header.Machine, 0x1234);
+    assert_eq!(header.machine(), None);
+    assert!(!header.is_arm());
+    assert_eq!(pefile.relocations()?.next().unwrap()?.relocations[0].kind, RelocationType::HighLow);
+    Ok(())
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+use std::path::PathBuf;
+
+const RT_MESSAGETABLE: u16 = 11;
+
+fn parameter_file(lang: u16, blocks: &[(u32, &[&str])]) -> Result<PEFile> {
+    let resources = ResourceBuilder::new()
+        .add(RT_MESSAGETABLE, 1, lang, message_table(blocks))
+        .build(0x1000);
+    let size = resources.data.len() as u32;
+    PEFile::from_data(
+        PEBuilder::new(false)
+            .directory(IMAGE_DIRECTORY_ENTRY_RESOURCE, 0x1000, size)
+            .section(".rsrc", resources)
+            .build(),
+    )
+}
+
+fn msaudite() -> Result<PEFile> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    PEFile::new(PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir)))
+}
+
+#[test]
+fn expand_parameters() -> Result<()> {
+    let msaudite = msaudite()?;
+    let objects = parameter_file(0x0409, &[(1833, &["Granted\r\n", "Denied\r\n"])])?;
+    let others = parameter_file(0x0409, &[(1834, &["Shadowed\r\n"]), (1900, &["Other\r\n"])])?;
+    let resolver = MessageResolver::new(&msaudite)
+        .with_parameter_file(&objects)
+        .with_parameter_file(&others);
+
+    assert_eq!(
+        resolver.expand_parameters("%%1833, %%1834 and %%1900; %%9999, %%x, 100%%")?,
+        "Granted, Denied and Other; %%9999, %%x, 100%%"
+    );
+
+    let text = resolver.format(516, &["%%1833".into()])?.unwrap();
+    assert!(text.ends_with("Number of audit messages discarded:\tGranted\r\n"));
+
+    assert!(resolver.format(4096, &[])?.is_none());
+    Ok(())
+}
+
+#[test]
+fn parameter_languages() -> Result<()> {
+    let msaudite = msaudite()?;
+    let german = parameter_file(0x0407, &[(1833, &["Gewährt\r\n"])])?;
+
+    let resolver = MessageResolver::new(&msaudite).with_parameter_file(&german);
+    assert_eq!(resolver.parameter(1833)?.as_deref(), Some("Gewährt"));
+
+    // messages which are not found in the parameter files are searched in the message file
+    assert_eq!(resolver.parameter(2)?.as_deref(), Some("Logon/Logoff"));
+    assert_eq!(resolver.parameter(4096)?, None);
+    Ok(())
+}
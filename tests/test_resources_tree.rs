@@ -0,0 +1,109 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+use std::path::PathBuf;
+
+fn image_with_resources() -> Result<PEFile> {
+    let resources = ResourceBuilder::new()
+        .add(10u16, "CONFIG", 0x0409, b"english".to_vec())
+        .add(10u16, "CONFIG", 0x0407, b"deutsch".to_vec())
+        .add(10u16, 7u16, 0x0409, b"seven".to_vec())
+        .add("CUSTOM", 1u16, 0, b"custom".to_vec())
+        .build(0x1000);
+    let size = resources.data.len() as u32;
+    PEFile::from_data(
+        PEBuilder::new(true)
+            .directory(IMAGE_DIRECTORY_ENTRY_RESOURCE, 0x1000, size)
+            .section(".rsrc", resources)
+            .build(),
+    )
+}
+
+#[test]
+fn tree_structure() -> Result<()> {
+    let pefile = image_with_resources()?;
+    let tree = pefile.resources_tree()?;
+
+    let rcdata = tree.get_type(ResourceType::RT_RCDATA).unwrap();
+    assert_eq!(rcdata.identifier, EntryIdentifier::Id(10));
+    assert_eq!(rcdata.names.len(), 2);
+
+    let config = tree.get(ResourceType::RT_RCDATA, "CONFIG").unwrap();
+    let languages: Vec<_> = config.languages.iter().map(|l| l.lang_id()).collect();
+    assert_eq!(languages.len(), 2);
+    assert!(languages.contains(&Some(0x0409)));
+    assert!(languages.contains(&Some(0x0407)));
+
+    let custom = tree.find("CUSTOM", 1, None).unwrap();
+    assert_eq!(custom.size, 6);
+    assert_eq!(custom.bytes(&pefile)?, b"custom");
+
+    assert_eq!(tree.iter().count(), 4);
+    assert_eq!(tree.iter_type(ResourceType::RT_RCDATA).count(), 3);
+    assert!(tree.get_type(ResourceType::RT_MESSAGETABLE).is_none());
+    Ok(())
+}
+
+#[test]
+fn find_by_language() -> Result<()> {
+    let pefile = image_with_resources()?;
+    let tree = pefile.resources_tree()?;
+
+    let german = tree.find(ResourceType::RT_RCDATA, "CONFIG", Some(0x0407)).unwrap();
+    assert_eq!(german.bytes(&pefile)?, b"deutsch");
+    let english = tree.find(ResourceType::RT_RCDATA, "CONFIG", Some(0x0409)).unwrap();
+    assert_eq!(english.bytes(&pefile)?, b"english");
+
+    // falls back to the first language
+    let other = tree.find(ResourceType::RT_RCDATA, 7, Some(0x040c)).unwrap();
+    assert_eq!(other.bytes(&pefile)?, b"seven");
+    assert!(tree.find(ResourceType::RT_RCDATA, 8, None).is_none());
+    Ok(())
+}
+
+#[derive(Default)]
+struct CountingVisitor {
+    directories: usize,
+    entries: usize,
+    depth: usize,
+    max_depth: usize,
+}
+
+impl ResourceDirectoryVisitor for CountingVisitor {
+    fn enter_resource_directory(&mut self, _: &IMAGE_RESOURCE_DIRECTORY, _: &EntryIdentifier) -> Result<()> {
+        self.directories += 1;
+        self.depth += 1;
+        self.max_depth = self.max_depth.max(self.depth);
+        Ok(())
+    }
+    fn leave_resource_directory(&mut self, _: &IMAGE_RESOURCE_DIRECTORY, _: &EntryIdentifier) -> Result<()> {
+        self.depth -= 1;
+        Ok(())
+    }
+    fn visit_resource_data_entry(&mut self, _: &IMAGE_RESOURCE_DATA_ENTRY, _: &EntryIdentifier) -> Result<()> {
+        self.entries += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn custom_visitor() -> Result<()> {
+    let pefile = image_with_resources()?;
+    let mut visitor = CountingVisitor::default();
+    pefile.visit_resource_tree(&mut visitor)?;
+
+    // root, two types, three names
+    assert_eq!(visitor.directories, 6);
+    assert_eq!(visitor.entries, 4);
+    assert_eq!(visitor.max_depth, 3);
+    assert_eq!(visitor.depth, 0);
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let msaudite = PEFile::new(PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir)))?;
+    let tree = msaudite.resources_tree()?;
+    let mut visitor = CountingVisitor::default();
+    msaudite.visit_resource_tree(&mut visitor)?;
+    assert_eq!(visitor.entries, tree.iter().count());
+    Ok(())
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+
+const RT_STRING: u16 = 6;
+
+/// encodes a bundle of 16 strings, where missing strings are empty
+fn string_bundle(strings: &[&str]) -> Vec<u8> {
+    let mut data = Vec::new();
+    for index in 0..16 {
+        let text: Vec<u16> = strings.get(index).map(|s| s.encode_utf16().collect()).unwrap_or_default();
+        data.extend_from_slice(&(text.len() as u16).to_le_bytes());
+        for c in text {
+            data.extend_from_slice(&c.to_le_bytes());
+        }
+    }
+    data
+}
+
+fn image_with_strings(resources: ResourceBuilder) -> Result<PEFile> {
+    let resources = resources.build(0x1000);
+    let size = resources.data.len() as u32;
+    PEFile::from_data(
+        PEBuilder::new(false)
+            .directory(IMAGE_DIRECTORY_ENTRY_RESOURCE, 0x1000, size)
+            .section(".rsrc", resources)
+            .build(),
+    )
+}
+
+fn string_image() -> Result<PEFile> {
+    image_with_strings(
+        ResourceBuilder::new()
+            .add(RT_STRING, 1u16, 0x0409, string_bundle(&["", "first", "", "third"]))
+            .add(RT_STRING, 3u16, 0x0409, string_bundle(&["thirty-two"]))
+            .add(RT_STRING, 1u16, 0x0407, string_bundle(&["", "erste"])),
+    )
+}
+
+#[test]
+fn iterate_strings() -> Result<()> {
+    let pefile = string_image()?;
+    let mut strings: Vec<_> = pefile
+        .string_table()?
+        .map(|s| s.map(|s| (s.id, s.lang_id, s.text)))
+        .collect::<Result<_>>()?;
+    strings.sort();
+    assert_eq!(
+        strings,
+        vec![
+            (1, 0x0407, "erste".to_string()),
+            (1, 0x0409, "first".to_string()),
+            (3, 0x0409, "third".to_string()),
+            (32, 0x0409, "thirty-two".to_string()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn lookup_string() -> Result<()> {
+    let pefile = string_image()?;
+    let string = pefile.string(32, 0x0409)?.unwrap();
+    assert_eq!(string.text, "thirty-two");
+    assert_eq!(string.language().name(), Some("en-US"));
+    assert_eq!(pefile.string(1, 0x0407)?.unwrap().text, "erste");
+
+    // empty strings, missing bundles and missing languages
+    assert!(pefile.string(0, 0x0409)?.is_none());
+    assert!(pefile.string(16, 0x0409)?.is_none());
+    assert!(pefile.string(3, 0x0407)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn truncated_bundle() -> Result<()> {
+    let mut bundle = string_bundle(&["complete", "truncated"]);
+    bundle.truncate(30);
+    let pefile = image_with_strings(
+        ResourceBuilder::new()
+            .add(RT_STRING, 1u16, 0x0409, bundle)
+            .add(RT_STRING, 2u16, 0x0409, string_bundle(&["never reached"])),
+    )?;
+
+    let results: Vec<_> = pefile.string_table()?.collect();
+    assert_eq!(results.len(), 1);
+    assert!(matches!(results[0], Err(Error::Truncated { .. })));
+    assert!(pefile.string(0, 0x0409).is_err());
+    assert_eq!(pefile.string(16, 0x0409)?.unwrap().text, "never reached");
+    Ok(())
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+use std::path::PathBuf;
+
+const RT_VERSION: u16 = 16;
+
+/// encodes a node of a VS_VERSIONINFO tree
+fn block(key: &str, is_text: bool, value: &[u8], children: &[Vec<u8>]) -> Vec<u8> {
+    let mut data = vec![0u8; 6];
+    for c in key.encode_utf16().chain(Some(0)) {
+        data.extend_from_slice(&c.to_le_bytes());
+    }
+    data.resize(data.len().next_multiple_of(4), 0);
+    data.extend_from_slice(value);
+    for child in children {
+        data.resize(data.len().next_multiple_of(4), 0);
+        data.extend_from_slice(child);
+    }
+    let value_length = if is_text { value.len() / 2 } else { value.len() };
+    let length = data.len() as u16;
+    data[0..2].copy_from_slice(&length.to_le_bytes());
+    data[2..4].copy_from_slice(&(value_length as u16).to_le_bytes());
+    data[4..6].copy_from_slice(&(is_text as u16).to_le_bytes());
+    data
+}
+
+fn string(key: &str, value: &str) -> Vec<u8> {
+    let value: Vec<u8> = value.encode_utf16().chain(Some(0)).flat_map(|c| c.to_le_bytes()).collect();
+    block(key, true, &value, &[])
+}
+
+fn fixed_file_info() -> Vec<u8> {
+    let fields: [u32; 13] = [0xfeef04bd, 0x10000, 0x0002_0001, 0x0003_0004, 0x0005_0006, 0, 0x3f, 0, 4, 1, 0, 0, 0];
+    fields.iter().flat_map(|f| f.to_le_bytes()).collect()
+}
+
+fn image_with_version(version: Vec<u8>) -> Result<PEFile> {
+    let resources = ResourceBuilder::new()
+        .add(RT_VERSION, 1u16, 0x0409, version)
+        .build(0x1000);
+    let size = resources.data.len() as u32;
+    PEFile::from_data(
+        PEBuilder::new(false)
+            .directory(IMAGE_DIRECTORY_ENTRY_RESOURCE, 0x1000, size)
+            .section(".rsrc", resources)
+            .build(),
+    )
+}
+
+#[test]
+fn synthetic_version_info() -> Result<()> {
+    let version = block("VS_VERSION_INFO", false, &fixed_file_info(), &[
+        block("StringFileInfo", true, &[], &[
+            block("040904b0", true, &[], &[
+                string("CompanyName", "Example Corp"),
+                string("FileVersion", "1.2.3.4"),
+            ]),
+            block("040704e4", true, &[], &[
+                string("CompanyName", "Beispiel GmbH"),
+            ]),
+        ]),
+        block("VarFileInfo", true, &[], &[
+            block("Translation", false, &[0x09, 0x04, 0xb0, 0x04, 0x07, 0x04, 0xe4, 0x04], &[]),
+        ]),
+    ]);
+    let pefile = image_with_version(version)?;
+    let info = pefile.version_info()?.unwrap();
+
+    let fixed = info.fixed.unwrap();
+    assert_eq!(fixed.file_version(), [2, 1, 3, 4]);
+    assert_eq!(fixed.product_version(), [5, 6, 0, 0]);
+
+    assert_eq!(info.string_tables.len(), 2);
+    assert_eq!(info.company_name(), Some("Example Corp"));
+    assert_eq!(info.file_version(), Some("1.2.3.4"));
+    assert_eq!(info.product_version(), None);
+    let german = info.string_table(0x0407, 1252).unwrap();
+    assert_eq!(german.get("CompanyName"), Some("Beispiel GmbH"));
+    assert_eq!(info.translations, vec![(0x0409, 1200), (0x0407, 1252)]);
+    Ok(())
+}
+
+#[test]
+fn invalid_version_info() -> Result<()> {
+    let mut fixed = fixed_file_info();
+    fixed[0] = 0;
+    let pefile = image_with_version(block("VS_VERSION_INFO", false, &fixed, &[]))?;
+    assert!(matches!(pefile.version_info(), Err(Error::InvalidData { .. })));
+
+    let pefile = image_with_version(block("SOMETHING_ELSE", false, &[], &[]))?;
+    assert!(pefile.version_info().is_err());
+
+    let pefile = PEFile::from_data(PEBuilder::new(false).build())?;
+    assert!(pefile.version_info()?.is_none());
+    Ok(())
+}
+
+#[test]
+fn deeply_nested_version_info() -> Result<()> {
+    // every block has an empty key and contains the next block
+    let size = 64000;
+    let mut version = Vec::with_capacity(size);
+    while version.len() < size {
+        let length = (size - version.len()) as u16;
+        version.extend_from_slice(&length.to_le_bytes());
+        version.extend_from_slice(&[0, 0, 1, 0, 0, 0]);
+    }
+    let pefile = image_with_version(version)?;
+    assert!(matches!(pefile.version_info(), Err(Error::InvalidData { .. })));
+    Ok(())
+}
+
+#[test]
+fn msaudite_version_info() -> Result<()> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let pefile = PEFile::new(PathBuf::from(format!("{}/samples/msaudite.dll", manifest_dir)))?;
+    let info = pefile.version_info()?.unwrap();
+
+    assert_eq!(info.fixed.unwrap().file_version(), [10, 0, 14393, 0]);
+    assert_eq!(info.company_name(), Some("Microsoft Corporation"));
+    assert_eq!(info.original_filename(), Some("msaudite.dll"));
+    assert_eq!(info.product_version(), Some("10.0.14393.0"));
+    assert_eq!(info.translations, vec![(0x0409, 1200)]);
+    Ok(())
+}
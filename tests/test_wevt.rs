@@ -0,0 +1,286 @@
+mod common;
+
+use common::*;
+use libpefile::*;
+
+fn push_name(data: &mut SectionData, name: &str) -> u32 {
+    let chars: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+    let offset = data.push_u32(4 + 2 * chars.len() as u32);
+    for c in chars {
+        data.push_u16(c);
+    }
+    data.align(4);
+    offset
+}
+
+/// appends a name of binary XML, which is stored inline
+fn push_xml_name(data: &mut SectionData, name: &str) {
+    data.push_u16(0);
+    data.push_u16(name.encode_utf16().count() as u16);
+    for c in name.encode_utf16() {
+        data.push_u16(c);
+    }
+    data.push_u16(0);
+}
+
+fn push_xml_string(data: &mut SectionData, value: &str) {
+    data.push(&[0x05, 0x01]);
+    data.push_u16(value.encode_utf16().count() as u16);
+    for c in value.encode_utf16() {
+        data.push_u16(c);
+    }
+}
+
+/// `<Data Name="name">` followed by a substitution
+fn push_data_element(data: &mut SectionData, name: &str, index: u16, value_type: u8, optional: bool) {
+    data.push(&[0x41, 0xff, 0xff]);
+    data.push_u32(0);
+    push_xml_name(data, "Data");
+    data.push_u32(0);
+    data.push(&[0x06]);
+    push_xml_name(data, "Name");
+    push_xml_string(data, name);
+    data.push(&[0x02]);
+    data.push(&[if optional { 0x0e } else { 0x0d }]);
+    data.push_u16(index);
+    data.push(&[value_type, 0x04]);
+}
+
+/// creates a WEVT_TEMPLATE resource with a single provider, which defines a single event
+fn wevt_template() -> Vec<u8> {
+    let mut data = SectionData::new(0);
+    data.push(b"CRIM");
+    data.push_u32(0);
+    data.push_u16(3);
+    data.push_u16(1);
+    data.push_u32(1);
+    data.push(&[0x25, 0x96, 0x84, 0x54, 0x78, 0x54, 0x94, 0x49, 0xa5, 0xba, 0x3e, 0x3b, 0x03, 0x28, 0xc3, 0x0d]);
+    let provider_offset = data.push_u32(0);
+
+    let provider = data.push(b"WEVT");
+    data.patch_u32(provider_offset, provider);
+    data.push_u32(0);
+    data.push_u32(0x9000_0001);
+    data.push_u32(8);
+    data.push_u32(0);
+    let descriptors: Vec<u32> = (0..8).map(|_| { let d = data.push_u32(0); data.push_u32(0); d }).collect();
+
+    // channels
+    let chan = data.push(b"CHAN");
+    data.push_u32(0);
+    data.push_u32(1);
+    data.push_u32(16);
+    let chan_name = data.push_u32(0);
+    data.push_u32(0);
+    data.push_u32(0x9000_0002);
+    let name = push_name(&mut data, "Security");
+    data.patch_u32(chan_name, name);
+
+    // levels, tasks and opcodes
+    let levl = data.push(b"LEVL");
+    data.push_u32(0);
+    data.push_u32(1);
+    let level_definition = data.push_u32(4);
+    data.push_u32(0x5000_0004);
+    let level_name = data.push_u32(0);
+    let name = push_name(&mut data, "win:Informational");
+    data.patch_u32(level_name, name);
+
+    let task = data.push(b"TASK");
+    data.push_u32(0);
+    data.push_u32(1);
+    let task_definition = data.push_u32(12544);
+    data.push_u32(0x7000_3100);
+    data.push(&[0; 16]);
+    let task_name = data.push_u32(0);
+    let name = push_name(&mut data, "Logon");
+    data.patch_u32(task_name, name);
+
+    let opco = data.push(b"OPCO");
+    data.push_u32(0);
+    data.push_u32(1);
+    let opcode_definition = data.push_u32(0);
+    data.push_u32(0x3000_0000);
+    let opcode_name = data.push_u32(0);
+    let name = push_name(&mut data, "win:Info");
+    data.patch_u32(opcode_name, name);
+
+    let keyw = data.push(b"KEYW");
+    data.push_u32(0);
+    data.push_u32(1);
+    data.push_u64(0x0020_0000_0000_0000);
+    data.push_u32(0x1000_0035);
+    let keyword_name = data.push_u32(0);
+    let name = push_name(&mut data, "Audit Success");
+    data.patch_u32(keyword_name, name);
+
+    // maps: the offset of the first map is not stored
+    let maps = data.push(b"MAPS");
+    data.push_u32(0);
+    data.push_u32(2);
+    let second_map = data.push_u32(0);
+    for (idx, signature) in [b"VMAP", b"BMAP"].iter().enumerate() {
+        let map = data.push(*signature);
+        if idx == 1 {
+            data.patch_u32(second_map, map);
+        }
+        data.push_u32(0);
+        let map_name = data.push_u32(0);
+        data.push_u32(0);
+        data.push_u32(2);
+        data.push_u32(1);
+        data.push_u32(0xd000_0001 + idx as u32);
+        data.push_u32(2);
+        data.push_u32(0xffff_ffff);
+        let name = push_name(&mut data, if idx == 0 { "LogonTypes" } else { "AccessFlags" });
+        data.patch_u32(map_name, name);
+    }
+
+    // templates
+    let ttbl = data.push(b"TTBL");
+    data.push_u32(0);
+    data.push_u32(1);
+    let template = data.push(b"TEMP");
+    let template_size = data.push_u32(0);
+    data.push_u32(2);
+    data.push_u32(2);
+    let items_offset = data.push_u32(0);
+    data.push_u32(1);
+    data.push(&[0x11; 16]);
+    data.push(&[0x0f, 0x01, 0x01, 0x00]);
+    data.push(&[0x01, 0xff, 0xff]);
+    data.push_u32(0);
+    push_xml_name(&mut data, "EventData");
+    data.push(&[0x02]);
+    push_data_element(&mut data, "SubjectUserSid", 0, 0x13, false);
+    push_data_element(&mut data, "TargetUserName", 1, 0x01, true);
+    data.push(&[0x04, 0x00]);
+    data.align(4);
+    let items = data.rva();
+    data.patch_u32(items_offset, items);
+    let mut item_names = Vec::new();
+    for (input_type, output_type) in [(0x13u8, 0x14u8), (0x01, 0x01)].iter() {
+        data.push_u32(0);
+        data.push(&[*input_type, *output_type, 0, 0]);
+        data.push_u32(0);
+        data.push_u16(1);
+        data.push_u16(0);
+        item_names.push(data.push_u32(0));
+    }
+    for (item_name, name) in item_names.into_iter().zip(["SubjectUserSid", "TargetUserName"].iter()) {
+        let offset = push_name(&mut data, name);
+        data.patch_u32(item_name, offset);
+    }
+    let size = data.rva() - template;
+    data.patch_u32(template_size, size);
+
+    // events
+    let evnt = data.push(b"EVNT");
+    data.push_u32(0);
+    data.push_u32(1);
+    data.push_u32(0);
+    data.push_u16(4624);
+    data.push(&[2, 16, 4, 0]);
+    data.push_u16(12544);
+    data.push_u64(0x8020_0000_0000_0000);
+    data.push_u32(0xb000_1210);
+    data.push_u32(template);
+    data.push_u32(opcode_definition);
+    data.push_u32(level_definition);
+    data.push_u32(task_definition);
+    data.push_u32(0);
+    data.push_u32(0);
+    data.push_u32(0);
+
+    for (descriptor, element) in descriptors.into_iter().zip([chan, levl, task, opco, keyw, maps, ttbl, evnt].iter()) {
+        data.patch_u32(descriptor, *element);
+    }
+    data.data
+}
+
+fn image_with_manifest(manifest: Vec<u8>) -> Result<PEFile> {
+    let resources = ResourceBuilder::new()
+        .add("WEVT_TEMPLATE", 1, 0, manifest)
+        .add(11, 1, 0x0409, message_table(&[(1, &["one"])]))
+        .build(0x1000);
+    let size = resources.data.len() as u32;
+    PEFile::from_data(
+        PEBuilder::new(true)
+            .directory(IMAGE_DIRECTORY_ENTRY_RESOURCE, 0x1000, size)
+            .section(".rsrc", resources)
+            .build(),
+    )
+}
+
+#[test]
+fn parse_manifest() -> Result<()> {
+    let pefile = image_with_manifest(wevt_template())?;
+    let manifest = pefile.wevt_manifest()?.unwrap();
+    assert_eq!((manifest.major_version, manifest.minor_version), (3, 1));
+    assert_eq!(manifest.providers.len(), 1);
+
+    let provider = &manifest.providers[0];
+    assert_eq!(provider.guid.unwrap().to_string(), "{54849625-5478-4994-a5ba-3e3b0328c30d}");
+    assert_eq!(provider.message_id, Some(0x9000_0001));
+
+    assert_eq!(provider.channels.len(), 1);
+    assert_eq!(provider.channels[0].name, "Security");
+    assert_eq!(provider.channels[0].id, 16);
+    assert_eq!(provider.keywords[0].name, "Audit Success");
+    assert_eq!(provider.keywords[0].mask, 0x0020_0000_0000_0000);
+
+    assert_eq!(provider.maps.len(), 2);
+    assert_eq!(provider.maps[0].kind, ValueMapKind::Value);
+    assert_eq!(provider.map("AccessFlags").unwrap().kind, ValueMapKind::Bitmap);
+    assert_eq!(provider.maps[0].entries[0].message_id, Some(0xd000_0001));
+    assert_eq!(provider.maps[0].entries[1].message_id, None);
+
+    assert_eq!(provider.events.len(), 1);
+    let event = &provider.events[0];
+    assert_eq!((event.id, event.version, event.keywords), (4624, 2, 0x8020_0000_0000_0000));
+    assert_eq!(event.message_id, Some(0xb000_1210));
+    assert_eq!(provider.level_of(event).unwrap().name, "win:Informational");
+    assert_eq!(provider.task_of(event).unwrap().name, "Logon");
+    assert_eq!(provider.opcode_of(event).unwrap().name, "win:Info");
+    assert_eq!(provider.channel_of(event).unwrap().name, "Security");
+    Ok(())
+}
+
+#[test]
+fn render_template() -> Result<()> {
+    let pefile = image_with_manifest(wevt_template())?;
+    let manifest = pefile.wevt_manifest()?.unwrap();
+    let provider = &manifest.providers[0];
+    let template = provider.template_of(&provider.events[0]).unwrap();
+
+    let names: Vec<_> = template.items.iter().map(|i| (i.name.as_str(), i.input_type, i.output_type)).collect();
+    assert_eq!(names, vec![("SubjectUserSid", 0x13, 0x14), ("TargetUserName", 0x01, 0x01)]);
+
+    assert_eq!(
+        template.to_xml(),
+        "<EventData><Data Name=\"SubjectUserSid\">$(0)</Data><Data Name=\"TargetUserName\">$(1)</Data></EventData>"
+    );
+    assert_eq!(
+        template.render(&["S-1-5-18".to_string(), "<alice>".to_string()]),
+        "<EventData><Data Name=\"SubjectUserSid\">S-1-5-18</Data><Data Name=\"TargetUserName\">&lt;alice&gt;</Data></EventData>"
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_and_broken_manifests() -> Result<()> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let msaudite = PEFile::new(format!("{}/samples/msaudite.dll", manifest_dir).into())?;
+    assert!(msaudite.wevt_manifest()?.is_none());
+
+    let mut manifest = wevt_template();
+    manifest[0..4].copy_from_slice(b"MIRC");
+    assert!(image_with_manifest(manifest)?.wevt_manifest().is_err());
+
+    let manifest = wevt_template();
+    for length in (0..manifest.len()).step_by(7) {
+        // must not panic
+        let _ = image_with_manifest(manifest[..length].to_vec())?.wevt_manifest();
+    }
+    Ok(())
+}